@@ -1,10 +1,13 @@
 use order_pdf_printer::footer::Footer;
-use order_pdf_printer::header::{Address, AddressableParty, Header};
+use order_pdf_printer::header::{
+    Address, AddressableParty, BankAccount, ElectronicAddress, Header, LegalRegistration,
+};
 
 pub fn get_header(label: &str) -> Header {
     Header {
         label: label.into(),
         logo: include_bytes!("logo.png").to_vec(),
+        logo_on_every_page: true,
         seller: AddressableParty {
             name: "Mr.Friendly B.V.".into(),
             address: Address {
@@ -13,8 +16,21 @@ pub fn get_header(label: &str) -> Header {
                 number: "11-07".into(),
                 street: "De Boomgaard".into(),
                 country: "Nederland".into(),
+                country_code: Some("NL".into()),
             },
-            vat_number: None,
+            vat_number: Some("NL856883566B01".into()),
+            electronic_address: Some(ElectronicAddress {
+                scheme: "0106".into(),
+                identifier: "67223370".into(),
+            }),
+            legal_registration: Some(LegalRegistration {
+                scheme: "0106".into(),
+                identifier: "67223370".into(),
+            }),
+            bank_account: Some(BankAccount {
+                iban: "NL75 INGB 0007 5383 83".into(),
+                bic: Some("INGBNL2A".into()),
+            }),
             department: None,
         },
         addressed_to: AddressableParty {
@@ -25,8 +41,12 @@ pub fn get_header(label: &str) -> Header {
                 number: "14".into(),
                 street: "Struikheiweg".into(),
                 country: "Nederland".into(),
+                country_code: Some("NL".into()),
             },
             vat_number: None,
+            electronic_address: None,
+            legal_registration: None,
+            bank_account: None,
             department: Some("Henk Knakworst".to_string()),
         },
     }
@@ -1,6 +1,12 @@
 use color_eyre::Result;
-use order_pdf_printer::invoice::{Currency, Invoice, InvoiceItem, InvoiceTotals};
-use order_pdf_printer::{DocumentConfiguration, PdfRenderable, RenderTarget, JVM};
+use order_pdf_printer::invoice::{
+    Currency, Invoice, InvoiceItem, InvoiceTotals, PaymentTerm, TotalsPlacement, VatRate,
+};
+use order_pdf_printer::{
+    DocumentConfiguration, DocumentMetadata, FontFamily, Locale, Margins, NaiveDate, Orientation,
+    PageNumbering, PaperSize, PdfRenderable, RenderTarget, Rounding, Translations, JVM,
+};
+use rust_decimal_macros::dec;
 use std::fs::File;
 use std::io::Write;
 
@@ -14,8 +20,21 @@ fn main() -> Result<()> {
 
     let render_target = RenderTarget::new(
         &DocumentConfiguration {
-            font_family: Some(include_bytes!("OpenSans-Regular.ttf").to_vec()),
+            font_family: Some(FontFamily::new(
+                include_bytes!("OpenSans-Regular.ttf").to_vec(),
+            )),
+            fallback_fonts: Vec::new(),
             font_size: Some(11.0),
+            translations: Translations::new(Locale::Dutch),
+            page_numbering: Some(PageNumbering::default()),
+            paper_size: PaperSize::A4,
+            orientation: Orientation::Portrait,
+            margins: Margins::default(),
+            metadata: DocumentMetadata {
+                creator: Some("Order PDF printer example".to_string()),
+                ..DocumentMetadata::default()
+            },
+            pdf_a: None,
         },
         &mut env,
     )?;
@@ -26,25 +45,31 @@ fn main() -> Result<()> {
         order_id: "10315".into(),
         reference: "Foobar".into(),
         invoice_id: "230307".into(),
-        invoice_date: "19-06-2023".to_string(),
+        invoice_date: NaiveDate::from_ymd_opt(2023, 6, 19).unwrap(),
         currency: Currency::Euro,
         note: Some("LET OP : REEDS BETAALD".into()),
+        strict_totals: None,
+        totals_placement: TotalsPlacement::Flow,
+        factur_x: false,
+        payment_qr_code: true,
+        rounding: Rounding::HalfUp,
         totals: InvoiceTotals {
-            total_excluding_vat: 242.95,
-            total_vat: 51.02,
-            total_including_vat: 293.97,
+            total_excluding_vat: dec!(242.95),
+            total_vat: dec!(51.02),
+            total_including_vat: dec!(293.97),
         },
 
-        expiration_date: "19-07-2023".to_string(),
+        payment_term: PaymentTerm::Net(30),
         items: vec![
             InvoiceItem {
                 identifier: "16005-3".to_string(),
                 description: "Set 3 stuks Mr.Friendly Filter Globe (nat)".to_string(),
                 quantity: 1,
-                price_per_unit: 59.95,
-                discount_percentage: 10.0,
-                subtotal_price_per_unit: 59.95,
-                total_price: 59.95,
+                price_per_unit: dec!(59.95),
+                discount_percentage: dec!(10.0),
+                subtotal_price_per_unit: dec!(59.95),
+                total_price: dec!(59.95),
+                vat_rate: VatRate::Percentage(dec!(21)),
             };
             3
         ],
@@ -1,6 +1,11 @@
 use color_eyre::Result;
-use order_pdf_printer::packing_slip::{ArticlePackingInfo, PackingSlip};
-use order_pdf_printer::{DocumentConfiguration, PdfRenderable, RenderTarget, JVM};
+use order_pdf_printer::packing_slip::{
+    ArticleBarcode, ArticlePackingInfo, PackingSlip, PackingSlipIdBarcode,
+};
+use order_pdf_printer::{
+    DocumentConfiguration, DocumentMetadata, FontFamily, Locale, Margins, NaiveDate, Orientation,
+    PageNumbering, PaperSize, PdfRenderable, RenderTarget, Translations, JVM,
+};
 use std::fs::File;
 use std::io::Write;
 
@@ -14,8 +19,21 @@ fn main() -> Result<()> {
 
     let render_target = RenderTarget::new(
         &DocumentConfiguration {
-            font_family: Some(include_bytes!("OpenSans-Regular.ttf").to_vec()),
+            font_family: Some(FontFamily::new(
+                include_bytes!("OpenSans-Regular.ttf").to_vec(),
+            )),
+            fallback_fonts: Vec::new(),
             font_size: Some(11.0),
+            translations: Translations::new(Locale::Dutch),
+            page_numbering: Some(PageNumbering::default()),
+            paper_size: PaperSize::A4,
+            orientation: Orientation::Portrait,
+            margins: Margins::default(),
+            metadata: DocumentMetadata {
+                creator: Some("Order PDF printer example".to_string()),
+                ..DocumentMetadata::default()
+            },
+            pdf_a: None,
         },
         &mut env,
     )?;
@@ -26,7 +44,8 @@ fn main() -> Result<()> {
         reference_id: "10273".into(),
         order_id: "23138".to_string(),
         packing_slip_id: "880".to_string(),
-        delivery_date: "7-2-2023".to_string(),
+        delivery_date: NaiveDate::from_ymd_opt(2023, 2, 7).unwrap(),
+        packing_slip_id_barcode: Some(PackingSlipIdBarcode::Code128),
         items: vec![
             ArticlePackingInfo {
                 identifier: "15001A".to_string(),
@@ -34,6 +53,7 @@ fn main() -> Result<()> {
                 quantity_delivered: 1,
                 quantity_ordered: 1,
                 quantity_backorder: 0,
+                barcode: Some(ArticleBarcode::Ean13("8712345678906".to_string())),
             },
             ArticlePackingInfo {
                 identifier: "15020".to_string(),
@@ -41,6 +61,7 @@ fn main() -> Result<()> {
                 quantity_delivered: 1,
                 quantity_ordered: 1,
                 quantity_backorder: 0,
+                barcode: Some(ArticleBarcode::Code128("15020".to_string())),
             },
             ArticlePackingInfo {
                 identifier: "16502".to_string(),
@@ -48,6 +69,7 @@ fn main() -> Result<()> {
                 quantity_delivered: 1,
                 quantity_ordered: 1,
                 quantity_backorder: 0,
+                barcode: None,
             },
         ],
     };
@@ -0,0 +1,59 @@
+use crate::pool::{render_document, Job};
+use crate::{Error, PdfRenderable, RenderPool};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+impl RenderPool {
+    /// Render a document to PDF bytes without blocking the async executor.
+    /// The document is rendered on one of the pool's JVM-attached worker threads.
+    ///
+    /// Dropping the returned future cancels the job if a worker has not yet picked it up.
+    /// A job that is already being rendered runs to completion, its result is discarded.
+    /// The same applies when `timeout` elapses.
+    ///
+    /// # Errors
+    ///
+    /// - If the JVM cannot be started
+    /// - If a JNI error occurs while rendering
+    /// - If rendering panicked
+    /// - If the worker threads are no longer available
+    /// - If the job did not finish within `timeout`
+    pub async fn render_async<D>(
+        &self,
+        document: D,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>, Error>
+    where
+        D: PdfRenderable + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let config = self.config().clone();
+        let job: Job = Box::new(move |env| {
+            // The caller is no longer interested in the result
+            if tx.is_closed() {
+                return;
+            }
+
+            let _ = tx.send(render_document(&document, &config, env));
+        });
+
+        if self.is_started() {
+            self.submit(job)?;
+        } else {
+            // Starting the JVM and the workers blocks for seconds, keep it off the executor
+            let pool = self.clone();
+            tokio::task::spawn_blocking(move || pool.submit(job))
+                .await
+                .map_err(|_| Error::PoolUnavailable)??;
+        }
+
+        let result = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, rx)
+                .await
+                .map_err(|_| Error::Timeout(timeout))?,
+            None => rx.await,
+        };
+
+        result.map_err(|_| Error::PoolUnavailable)?
+    }
+}
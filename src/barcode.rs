@@ -0,0 +1,491 @@
+//! Barcodes, drawn as bitmaps because the bundled iText modules cannot draw them.
+
+use crate::Error;
+use itext::itext::io::ImageData;
+use itext::itext::layout::Image;
+use jni::JNIEnv;
+use qrcodegen::{QrCode, QrCodeEcc};
+
+/// The width of the light border around QR codes, in modules
+const QR_QUIET_ZONE: usize = 4;
+
+/// The amount of pixels per module in the bitmap, so viewers which smooth images keep the edges sharp
+const PIXELS_PER_MODULE: usize = 4;
+
+/// A barcode as a grid of dark and light modules
+pub(crate) struct Bitmap {
+    width: usize,
+    height: usize,
+    /// Whether each module is dark, row by row from the top
+    modules: Vec<bool>,
+}
+
+impl Bitmap {
+    /// The width of the barcode in modules, including the quiet zone
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Create an image of the barcode with the provided size in points.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub fn to_image<'a>(
+        &self,
+        width: f32,
+        height: f32,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<Image<'a>, Error> {
+        let image = Image::new(ImageData::new(env, &self.to_bmp())?, env)?;
+        image.set_width(width, env)?.set_height(height, env)?;
+        Ok(image)
+    }
+
+    /// Encode the bitmap as 24-bit BMP image
+    fn to_bmp(&self) -> Vec<u8> {
+        const HEADER_SIZE: usize = 54;
+
+        let width = self.width * PIXELS_PER_MODULE;
+        let height = self.height * PIXELS_PER_MODULE;
+        // Rows are padded to a multiple of 4 bytes
+        let row_size = (width * 3).div_ceil(4) * 4;
+        let image_size = row_size * height;
+
+        let mut bmp = Vec::with_capacity(HEADER_SIZE + image_size);
+        // File header
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&((HEADER_SIZE + image_size) as u32).to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        // Info header
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&(width as i32).to_le_bytes());
+        bmp.extend_from_slice(&(height as i32).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&24u16.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&(image_size as u32).to_le_bytes());
+        // 72 DPI, in pixels per meter
+        bmp.extend_from_slice(&2835u32.to_le_bytes());
+        bmp.extend_from_slice(&2835u32.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+
+        // Rows are stored from the bottom up
+        for y in (0..height).rev() {
+            let row_start = bmp.len();
+            for x in 0..width {
+                let dark = self.modules[y / PIXELS_PER_MODULE * self.width + x / PIXELS_PER_MODULE];
+                let value = if dark { 0x00 } else { 0xFF };
+                bmp.extend_from_slice(&[value; 3]);
+            }
+            bmp.resize(row_start + row_size, 0);
+        }
+
+        bmp
+    }
+}
+
+/// Encode data as QR code in byte mode, with at least error correction level M.
+/// Returns None if the data does not fit in a QR code.
+pub(crate) fn qr_code(data: &[u8]) -> Option<Bitmap> {
+    let qr = QrCode::encode_binary(data, QrCodeEcc::Medium).ok()?;
+    let qr_size = qr.size() as usize;
+    let size = qr_size + 2 * QR_QUIET_ZONE;
+
+    let mut modules = vec![false; size * size];
+    for y in 0..qr_size {
+        for x in 0..qr_size {
+            modules[(y + QR_QUIET_ZONE) * size + x + QR_QUIET_ZONE] =
+                qr.get_module(x as i32, y as i32);
+        }
+    }
+
+    Some(Bitmap {
+        width: size,
+        height: size,
+        modules,
+    })
+}
+
+/// The width of the light border around linear barcodes, in modules
+const LINEAR_QUIET_ZONE: usize = 10;
+
+/// The widths of the alternating bars and spaces of each Code 128 symbol, by value.
+/// Values 103 to 105 are the start symbols of code sets A to C, 106 is the stop symbol.
+const CODE128_SYMBOLS: [&[u8]; 107] = [
+    b"212222", b"222122", b"222221", b"121223", b"121322", b"131222", b"122213", b"122312",
+    b"132212", b"221213", b"221312", b"231212", b"112232", b"122132", b"122231", b"113222",
+    b"123122", b"123221", b"223211", b"221132", b"221231", b"213212", b"223112", b"312131",
+    b"311222", b"321122", b"321221", b"312212", b"322112", b"322211", b"212123", b"212321",
+    b"232121", b"111323", b"131123", b"131321", b"112313", b"132113", b"132311", b"211313",
+    b"231113", b"231311", b"112133", b"112331", b"132131", b"113123", b"113321", b"133121",
+    b"313121", b"211331", b"231131", b"213113", b"213311", b"213131", b"311123", b"311321",
+    b"331121", b"312113", b"312311", b"332111", b"314111", b"221411", b"431111", b"111224",
+    b"111422", b"121124", b"121421", b"141122", b"141221", b"112214", b"112412", b"122114",
+    b"122411", b"142112", b"142211", b"241211", b"221114", b"413111", b"241112", b"134111",
+    b"111242", b"121142", b"121241", b"114212", b"124112", b"124211", b"411212", b"421112",
+    b"421211", b"212141", b"214121", b"412121", b"111143", b"111341", b"131141", b"114113",
+    b"114311", b"411113", b"411311", b"113141", b"114131", b"311141", b"411131", b"211412",
+    b"211214", b"211232", b"2331112",
+];
+
+/// The start symbol of Code 128 code set B, which encodes printable ASCII characters
+const CODE128_START_B: usize = 104;
+
+/// The start symbol of Code 128 code set C, which encodes pairs of digits
+const CODE128_START_C: usize = 105;
+
+/// The stop symbol of Code 128
+const CODE128_STOP: usize = 106;
+
+/// Switches from code set B to code set C
+const CODE128_CODE_C: usize = 99;
+
+/// Switches from code set C to code set B
+const CODE128_CODE_B: usize = 100;
+
+/// Encode text as Code 128 barcode, see [code128_values] for the code sets used.
+/// Returns None if the text is empty or contains characters other than printable ASCII.
+pub(crate) fn code128(text: &str) -> Option<Bitmap> {
+    if text.is_empty() || !text.bytes().all(|b| (b' '..=b'~').contains(&b)) {
+        return None;
+    }
+
+    let mut values = code128_values(text.as_bytes());
+
+    // The start symbol has weight 1, as does the first data symbol
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| idx.max(1) * value)
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+    values.push(CODE128_STOP);
+
+    let mut modules = vec![false; LINEAR_QUIET_ZONE];
+    for value in values {
+        for (idx, width) in CODE128_SYMBOLS[value].iter().enumerate() {
+            // Symbols start with a bar
+            let dark = idx % 2 == 0;
+            modules.extend(std::iter::repeat_n(dark, usize::from(width - b'0')));
+        }
+    }
+    modules.extend([false; LINEAR_QUIET_ZONE]);
+
+    Some(Bitmap {
+        width: modules.len(),
+        height: 1,
+        modules,
+    })
+}
+
+/// The values of the start symbol and data symbols encoding printable ASCII text.
+/// Text uses code set B. Runs of digits use code set C, which encodes pairs of digits,
+/// where that makes the barcode shorter: a text of an even amount of digits,
+/// four or more digits at the start or end of the text, or six or more in between.
+fn code128_values(text: &[u8]) -> Vec<usize> {
+    let digits_at = |idx: usize| {
+        text[idx..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+
+    let leading_digits = digits_at(0);
+    let mut set_c =
+        leading_digits >= 4 || (leading_digits == text.len() && leading_digits % 2 == 0);
+    let mut values = vec![if set_c {
+        CODE128_START_C
+    } else {
+        CODE128_START_B
+    }];
+
+    let mut idx = 0;
+    while idx < text.len() {
+        let digits = digits_at(idx);
+        if set_c {
+            if digits >= 2 {
+                values.push(usize::from((text[idx] - b'0') * 10 + text[idx + 1] - b'0'));
+                idx += 2;
+                continue;
+            }
+            values.push(CODE128_CODE_B);
+            set_c = false;
+        }
+
+        let ends_text = idx + digits == text.len();
+        if digits >= 6 || (digits >= 4 && ends_text) {
+            // An odd digit is encoded in code set B, so the rest of the run forms pairs
+            if digits % 2 == 1 {
+                values.push(usize::from(text[idx] - b' '));
+                idx += 1;
+            }
+            values.push(CODE128_CODE_C);
+            set_c = true;
+            continue;
+        }
+
+        values.push(usize::from(text[idx] - b' '));
+        idx += 1;
+    }
+
+    values
+}
+
+/// The odd parity (set A) patterns of EAN-13 digits.
+/// The right-hand (set C) patterns are their complements, the even parity (set B) patterns their mirrored complements.
+const EAN13_SET_A: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+
+/// For each first digit, which of the six left-hand digits use even parity (set B),
+/// with the leftmost digit in the most significant bit
+const EAN13_FIRST_DIGIT_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+/// Whether text is a valid EAN-13, i.e. 13 digits of which the last is the correct check digit
+pub(crate) fn is_valid_ean13(text: &str) -> bool {
+    let digits = text.as_bytes();
+    if digits.len() != 13 || !digits.iter().all(u8::is_ascii_digit) {
+        return false;
+    }
+
+    // From the left, digits are alternately weighted 1 and 3
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(idx, digit)| u32::from(digit - b'0') * if idx % 2 == 0 { 1 } else { 3 })
+        .sum();
+    u32::from(digits[12] - b'0') == (10 - sum % 10) % 10
+}
+
+/// Encode a GTIN-13 as EAN-13 barcode.
+/// Returns None if the text is not a valid EAN-13, see [is_valid_ean13].
+pub(crate) fn ean13(text: &str) -> Option<Bitmap> {
+    if !is_valid_ean13(text) {
+        return None;
+    }
+
+    fn push_pattern(modules: &mut Vec<bool>, pattern: u8, length: usize) {
+        modules.extend((0..length).rev().map(|bit| pattern >> bit & 1 == 1));
+    }
+
+    let digits: Vec<usize> = text.bytes().map(|b| usize::from(b - b'0')).collect();
+    let parity = EAN13_FIRST_DIGIT_PARITY[digits[0]];
+
+    // The quiet zone is wider on the left, where the first digit is printed in human-readable form
+    let mut modules = vec![false; 11];
+    push_pattern(&mut modules, 0b101, 3);
+    for (idx, &digit) in digits[1..7].iter().enumerate() {
+        let pattern = EAN13_SET_A[digit];
+        if parity >> (5 - idx) & 1 == 1 {
+            // Set B: the mirrored set C pattern
+            push_pattern(&mut modules, (!pattern & 0x7F).reverse_bits() >> 1, 7);
+        } else {
+            push_pattern(&mut modules, pattern, 7);
+        }
+    }
+    push_pattern(&mut modules, 0b01010, 5);
+    for &digit in &digits[7..] {
+        push_pattern(&mut modules, !EAN13_SET_A[digit] & 0x7F, 7);
+    }
+    push_pattern(&mut modules, 0b101, 3);
+    modules.extend([false; 7]);
+
+    Some(Bitmap {
+        width: modules.len(),
+        height: 1,
+        modules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The modules of a linear barcode, as `1` for dark and `0` for light modules
+    fn module_string(bitmap: &Bitmap) -> String {
+        assert_eq!(bitmap.height, 1);
+        bitmap
+            .modules
+            .iter()
+            .map(|&dark| if dark { '1' } else { '0' })
+            .collect()
+    }
+
+    /// The widths of the bars and spaces of a linear barcode, without the quiet zones
+    fn bar_widths(bitmap: &Bitmap) -> String {
+        let modules = module_string(bitmap);
+        let quiet_zone = "0".repeat(LINEAR_QUIET_ZONE);
+        let symbols = modules
+            .strip_prefix(&quiet_zone)
+            .and_then(|modules| modules.strip_suffix(&quiet_zone))
+            .expect("quiet zones");
+
+        let mut widths = String::new();
+        let mut run = 1;
+        let bytes = symbols.as_bytes();
+        for idx in 1..=bytes.len() {
+            if idx < bytes.len() && bytes[idx] == bytes[idx - 1] {
+                run += 1;
+            } else {
+                widths.push_str(&run.to_string());
+                run = 1;
+            }
+        }
+        widths
+    }
+
+    #[test]
+    fn code128_uses_set_b_for_text() {
+        let barcode = code128("PJJ123C").unwrap();
+        assert_eq!(barcode.width(), 132);
+        assert_eq!(
+            bar_widths(&barcode),
+            [
+                "211214", // Start B
+                "313121", // P
+                "112133", // J
+                "112133", // J
+                "123221", // 1
+                "223211", // 2
+                "221132", // 3
+                "131321", // C
+                "311321", // Checksum 55
+                "2331112",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn code128_uses_set_c_for_even_digits() {
+        let barcode = code128("1234").unwrap();
+        assert_eq!(barcode.width(), 77);
+        assert_eq!(
+            bar_widths(&barcode),
+            [
+                "211232", // Start C
+                "112232", // 12
+                "131123", // 34
+                "121241", // Checksum 82
+                "2331112",
+            ]
+            .concat()
+        );
+
+        // An odd amount of digits uses set B
+        assert!(bar_widths(&code128("123").unwrap()).starts_with("211214"));
+    }
+
+    #[test]
+    fn code128_uses_set_c_for_runs_of_digits() {
+        // Four or more digits at the start, the odd digit is encoded in set B
+        assert_eq!(code128_values(b"12345"), [105, 12, 34, 100, 21]);
+        assert_eq!(code128_values(b"15001AB"), [105, 15, 0, 100, 17, 33, 34]);
+        // Four or more digits at the end
+        assert_eq!(
+            code128_values(b"PS-000880"),
+            [104, 48, 51, 13, 99, 0, 8, 80]
+        );
+        assert_eq!(code128_values(b"AB12345"), [104, 33, 34, 17, 99, 23, 45]);
+        // Six or more digits in between
+        assert_eq!(
+            code128_values(b"A123456B"),
+            [104, 33, 99, 12, 34, 56, 100, 34]
+        );
+        assert_eq!(
+            code128_values(b"A12345B"),
+            [104, 33, 17, 18, 19, 20, 21, 34]
+        );
+        // Shorter runs stay in set B
+        assert_eq!(code128_values(b"A12"), [104, 33, 17, 18]);
+        assert_eq!(code128_values(b"12A"), [104, 17, 18, 33]);
+    }
+
+    #[test]
+    fn code128_switching_sets_shortens_barcode() {
+        // The symbols above plus the checksum, the stop symbol and the quiet zones.
+        // Set B alone would need one symbol per character.
+        assert_eq!(code128("15001AB").unwrap().width(), 8 * 11 + 13 + 20);
+        assert_eq!(code128("PS-000880").unwrap().width(), 9 * 11 + 13 + 20);
+    }
+
+    #[test]
+    fn code128_rejects_other_text() {
+        assert!(code128("").is_none());
+        assert!(code128("café").is_none());
+        assert!(code128("a\tb").is_none());
+    }
+
+    #[test]
+    fn ean13_encodes_gtin() {
+        let barcode = ean13("4006381333931").unwrap();
+        assert_eq!(barcode.width(), 113);
+        assert_eq!(
+            module_string(&barcode),
+            [
+                "00000000000", // Quiet zone
+                "101",         // Start
+                "0001101",     // 0, set A
+                "0100111",     // 0, set B
+                "0101111",     // 6, set A
+                "0111101",     // 3, set A
+                "0001001",     // 8, set B
+                "0110011",     // 1, set B
+                "01010",       // Center
+                "1000010",     // 3, set C
+                "1000010",     // 3, set C
+                "1000010",     // 3, set C
+                "1110100",     // 9, set C
+                "1000010",     // 3, set C
+                "1100110",     // 1, set C
+                "101",         // End
+                "0000000",     // Quiet zone
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn validates_ean13_check_digit() {
+        assert!(is_valid_ean13("4006381333931"));
+        assert!(is_valid_ean13("8712345678906"));
+        assert!(!is_valid_ean13("4006381333932"));
+        assert!(!is_valid_ean13("400638133393"));
+        assert!(!is_valid_ean13("40063813339310"));
+        assert!(!is_valid_ean13("400638133393a"));
+        assert!(ean13("4006381333932").is_none());
+    }
+
+    #[test]
+    fn qr_code_has_quiet_zone() {
+        // Version 1 is 21 modules wide
+        let qr = qr_code(b"230307").unwrap();
+        assert_eq!(qr.width(), 21 + 2 * QR_QUIET_ZONE);
+        assert_eq!(qr.height, qr.width);
+
+        let dark = |x: usize, y: usize| qr.modules[y * qr.width + x];
+        for i in 0..qr.width {
+            for border in [0, QR_QUIET_ZONE - 1, qr.width - 1] {
+                assert!(!dark(i, border) && !dark(border, i));
+            }
+        }
+        // The top left corner of the finder pattern
+        assert!(dark(QR_QUIET_ZONE, QR_QUIET_ZONE));
+    }
+
+    #[test]
+    fn qr_code_fits_epc_payloads() {
+        assert!(qr_code(&[b'a'; 331]).is_some());
+        // Version 40 holds at most 2331 bytes at error correction level M
+        assert!(qr_code(&[b'a'; 2331]).is_some());
+        assert!(qr_code(&[b'a'; 2332]).is_none());
+    }
+}
@@ -0,0 +1,173 @@
+//! EPC QR codes (EPC069-12, also known as GiroCode),
+//! which customers can scan with their banking app to pay an invoice.
+
+use crate::barcode::{qr_code, Bitmap};
+use crate::header::BankAccount;
+use crate::invoice::Invoice;
+use crate::validate::field_path;
+use crate::{Currency, Decimal, Error, ValidationIssue};
+
+/// The maximum length of the beneficiary's name, in characters
+const EPC_MAX_NAME_LENGTH: usize = 70;
+
+/// The maximum length of the remittance information, in characters
+const EPC_MAX_REMITTANCE_LENGTH: usize = 140;
+
+/// The maximum length of the whole payload, in bytes
+const EPC_MAX_PAYLOAD_LENGTH: usize = 331;
+
+impl Invoice {
+    /// The payload of the EPC QR code to pay this invoice:
+    /// a SEPA credit transfer of the total including VAT to the seller's bank account,
+    /// with the invoice ID as remittance information.
+    ///
+    /// # Errors
+    ///
+    /// If the invoice cannot be paid using an EPC QR code,
+    /// e.g. because its currency is not euro or the seller has no bank account
+    pub fn epc_payload(&self) -> Result<String, Error> {
+        let mut issues = Vec::new();
+        self.validate_epc_at("", &mut issues);
+        if !issues.is_empty() {
+            return Err(Error::Validation(issues));
+        }
+
+        let bank_account = self
+            .header
+            .seller
+            .bank_account
+            .as_ref()
+            .expect("bank account is validated");
+        Ok(self.build_epc_payload(bank_account))
+    }
+
+    /// Join the elements of the EPC QR code payload, without validating them
+    fn build_epc_payload(&self, bank_account: &BankAccount) -> String {
+        let amount = self.rounding.round(self.totals.total_including_vat, 2);
+        let elements = [
+            "BCD",
+            // Version 002 makes the BIC optional
+            "002",
+            // UTF-8
+            "1",
+            "SCT",
+            bank_account.bic.as_deref().unwrap_or_default(),
+            &self.header.seller.name,
+            &bank_account.normalized_iban(),
+            &format!("EUR{amount:.2}"),
+            // Purpose
+            "",
+            // Structured creditor reference, which the invoice ID is not
+            "",
+            &self.invoice_id,
+        ];
+        elements.join("\n")
+    }
+
+    /// Verify the invoice can be paid using an EPC QR code.
+    /// `path` is the path of the invoice within the document.
+    pub(crate) fn validate_epc_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        if self.currency != Currency::Euro {
+            issues.push(ValidationIssue {
+                path: field_path(path, "currency"),
+                message: "must be euro for EPC QR codes".to_string(),
+            });
+        }
+
+        let seller_path = field_path(path, "header.seller");
+        let seller = &self.header.seller;
+        match &seller.bank_account {
+            Some(bank_account) => {
+                // Multi-byte characters can exceed the limit even if all elements fit
+                let length = self.build_epc_payload(bank_account).len();
+                if length > EPC_MAX_PAYLOAD_LENGTH {
+                    issues.push(ValidationIssue {
+                        path: field_path(path, "payment_qr_code"),
+                        message: format!(
+                            "payload is {length} bytes, EPC QR codes allow at most {EPC_MAX_PAYLOAD_LENGTH}"
+                        ),
+                    });
+                }
+            }
+            None => issues.push(ValidationIssue {
+                path: field_path(&seller_path, "bank_account"),
+                message: "is required for EPC QR codes".to_string(),
+            }),
+        }
+        if seller.name.chars().count() > EPC_MAX_NAME_LENGTH {
+            issues.push(ValidationIssue {
+                path: field_path(&seller_path, "name"),
+                message: format!(
+                    "must be at most {EPC_MAX_NAME_LENGTH} characters for EPC QR codes"
+                ),
+            });
+        }
+
+        // Amounts between 0.01 and 999999999.99 can be transferred
+        let (min, max) = (Decimal::new(1, 2), Decimal::new(99_999_999_999, 2));
+        let total = self.totals.total_including_vat;
+        if !(min..=max).contains(&total) {
+            issues.push(ValidationIssue {
+                path: field_path(path, "totals.total_including_vat"),
+                message: format!("{total} is not between {min} and {max}"),
+            });
+        }
+
+        if self.invoice_id.chars().count() > EPC_MAX_REMITTANCE_LENGTH {
+            issues.push(ValidationIssue {
+                path: field_path(path, "invoice_id"),
+                message: format!(
+                    "must be at most {EPC_MAX_REMITTANCE_LENGTH} characters for EPC QR codes"
+                ),
+            });
+        }
+    }
+
+    /// The EPC QR code to pay this invoice, see [Invoice::epc_payload]
+    ///
+    /// # Errors
+    ///
+    /// If the invoice cannot be paid using an EPC QR code
+    pub(crate) fn epc_qr_code(&self) -> Result<Bitmap, Error> {
+        let payload = self.epc_payload()?;
+        // The length of the payload is validated, so it always fits
+        Ok(qr_code(payload.as_bytes()).expect("EPC payload fits in a QR code"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invoice::tests::sample_invoice;
+
+    #[test]
+    fn writes_payload() {
+        assert_eq!(
+            sample_invoice().epc_payload().unwrap(),
+            "BCD\n002\n1\nSCT\nINGBNL2A\nMr.Friendly B.V.\nNL75INGB0007538383\nEUR155.93\n\n\n230307"
+        );
+    }
+
+    #[test]
+    fn limits_payload_to_331_bytes() {
+        let mut invoice = sample_invoice();
+        // The elements are within their limits in characters, but take two bytes per character
+        invoice.header.seller.name = "é".repeat(EPC_MAX_NAME_LENGTH);
+        invoice.invoice_id = "é".repeat(EPC_MAX_REMITTANCE_LENGTH);
+
+        let Err(Error::Validation(issues)) = invoice.epc_payload() else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "payment_qr_code");
+        assert_eq!(
+            issues[0].message,
+            "payload is 475 bytes, EPC QR codes allow at most 331"
+        );
+
+        invoice.invoice_id = "é".repeat(40);
+        let payload = invoice.epc_payload().unwrap();
+        assert_eq!(payload.len(), 275);
+        assert!(invoice.epc_qr_code().is_ok());
+    }
+}
@@ -1,5 +1,11 @@
+use crate::ValidationIssue;
+use jni::objects::{JObject, JString, JThrowable};
+use jni::JNIEnv;
 use thiserror::Error;
 
+/// The exception iText's pdfa module throws when a document does not conform to its PDF/A level
+const PDF_A_CONFORMANCE_EXCEPTION: &str = "com.itextpdf.pdfa.PdfAConformanceException";
+
 /// Error which can occur while rendering a PDF document.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -9,8 +15,147 @@ pub enum Error {
     JvmStart(#[from] jni::errors::StartJvmError),
     #[error("{0}")]
     Jni(#[from] jni::errors::Error),
+    #[error("{class}: {}", message.as_deref().unwrap_or_default())]
+    Java {
+        /// The fully qualified class name of the exception
+        class: String,
+        /// The exception's message, if any
+        message: Option<String>,
+        /// The exception's stack trace, as printed by `Throwable#printStackTrace`
+        stack: String,
+    },
     #[error("{0}")]
     Io(#[from] std::io::Error),
     #[error("{0:?}")]
     ColorTransform(colors_transform::ParseError),
+    #[error(
+        "Document is invalid: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    Validation(Vec<ValidationIssue>),
+    #[error("Document does not conform to PDF/A: {reason}")]
+    PdfAConformance {
+        /// Why the document does not conform, e.g. the message of iText's `PdfAConformanceException`
+        reason: String,
+    },
+    #[error(
+        "PDF/A output requires iText's pdfa module on the classpath, see JvmConfig::classpath_jar"
+    )]
+    PdfAUnavailable,
+    #[error("Factur-X invoices must be written as PDF/A-3b")]
+    FacturXRequiresPdfA3,
+    #[error("The footer must be set before any content is added to the document")]
+    FooterAfterContent,
+    #[error("The document already has a footer")]
+    FooterAlreadySet,
+    #[error("The process-wide JVM is already running")]
+    JvmAlreadyRunning,
+    #[error("The render pool's worker threads are unavailable")]
+    PoolUnavailable,
+    #[error("Rendering panicked: {0}")]
+    RenderPanicked(String),
+    #[error("Rendering did not finish within {0:?}")]
+    Timeout(std::time::Duration),
+}
+
+impl Error {
+    /// Replace a bare [jni::errors::Error::JavaException] with the details of the pending
+    /// Java exception. The pending exception is cleared, after which the JNIEnv is usable again.
+    /// PDF/A conformance exceptions become [Error::PdfAConformance].
+    /// Any other error is returned unchanged.
+    pub fn resolve_java_exception(self, env: &mut JNIEnv<'_>) -> Self {
+        if !matches!(self, Self::Jni(jni::errors::Error::JavaException)) {
+            return self;
+        }
+
+        match env.exception_check() {
+            Ok(true) => {}
+            _ => return self,
+        }
+
+        let describe = env.with_local_frame(16, |env| {
+            let throwable = env.exception_occurred()?;
+            env.exception_clear()?;
+            describe_throwable(&throwable, env)
+        });
+
+        match describe {
+            Ok(Self::Java { class, message, .. }) if class == PDF_A_CONFORMANCE_EXCEPTION => {
+                Self::PdfAConformance {
+                    reason: message.unwrap_or_default(),
+                }
+            }
+            Ok(error) => error,
+            Err(_) => {
+                // Describing the exception threw as well, there's nothing more we can do
+                let _ = env.exception_clear();
+                self
+            }
+        }
+    }
+}
+
+/// Retrieve the class name, message and stack trace of a Java exception.
+///
+/// # Errors
+///
+/// If a JNI error occurs
+fn describe_throwable(
+    throwable: &JThrowable<'_>,
+    env: &mut JNIEnv<'_>,
+) -> Result<Error, jni::errors::Error> {
+    let class = env
+        .call_method(throwable, "getClass", "()Ljava/lang/Class;", &[])?
+        .l()?;
+    let class = env
+        .call_method(&class, "getName", "()Ljava/lang/String;", &[])?
+        .l()?;
+    let class = java_string(class, env)?.unwrap_or_default();
+
+    let message = env
+        .call_method(throwable, "getMessage", "()Ljava/lang/String;", &[])?
+        .l()?;
+    let message = java_string(message, env)?;
+
+    let writer = env.new_object("java/io/StringWriter", "()V", &[])?;
+    let print_writer = env.new_object(
+        "java/io/PrintWriter",
+        "(Ljava/io/Writer;)V",
+        &[(&writer).into()],
+    )?;
+    env.call_method(
+        throwable,
+        "printStackTrace",
+        "(Ljava/io/PrintWriter;)V",
+        &[(&print_writer).into()],
+    )?;
+    let stack = env
+        .call_method(&writer, "toString", "()Ljava/lang/String;", &[])?
+        .l()?;
+    let stack = java_string(stack, env)?.unwrap_or_default();
+
+    Ok(Error::Java {
+        class,
+        message,
+        stack,
+    })
+}
+
+/// Convert a `java.lang.String` to a Rust String.
+/// Returns None if the object is null.
+///
+/// # Errors
+///
+/// If a JNI error occurs
+fn java_string(
+    object: JObject<'_>,
+    env: &mut JNIEnv<'_>,
+) -> Result<Option<String>, jni::errors::Error> {
+    if object.is_null() {
+        return Ok(None);
+    }
+
+    let string = JString::from(object);
+    let string = env.get_string(&string)?.into();
+    Ok(Some(string))
 }
@@ -0,0 +1,446 @@
+//! Factur-X/ZUGFeRD e-invoices: invoices as UN/CEFACT Cross Industry Invoice (CII) XML,
+//! embedded in a PDF/A-3 document.
+
+use crate::header::AddressableParty;
+use crate::invoice::{Invoice, InvoiceItem, COMMERCIAL_INVOICE, SEPA_CREDIT_TRANSFER, UNIT_CODE};
+use crate::kernel::{AssociatedFile, PdfDocumentExt};
+use crate::xml::XmlWriter;
+use crate::{Error, NaiveDate, PdfAConformanceLevel, RenderTarget};
+use jni::JNIEnv;
+
+/// The name of the embedded XML file, prescribed by Factur-X
+const FACTUR_X_FILE_NAME: &str = "factur-x.xml";
+
+/// The identifier of the EN 16931 profile
+const EN_16931_GUIDELINE: &str = "urn:cen.eu:en16931:2017";
+
+/// XMP metadata identifying the document as Factur-X invoice,
+/// including the extension schema PDF/A requires for the Factur-X properties
+const FACTUR_X_XMP: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:pdfaExtension="http://www.aiim.org/pdfa/ns/extension/"
+        xmlns:pdfaSchema="http://www.aiim.org/pdfa/ns/schema#"
+        xmlns:pdfaProperty="http://www.aiim.org/pdfa/ns/property#">
+      <pdfaExtension:schemas>
+        <rdf:Bag>
+          <rdf:li rdf:parseType="Resource">
+            <pdfaSchema:schema>Factur-X PDFA Extension Schema</pdfaSchema:schema>
+            <pdfaSchema:namespaceURI>urn:factur-x:pdfa:CrossIndustryDocument:invoice:1p0#</pdfaSchema:namespaceURI>
+            <pdfaSchema:prefix>fx</pdfaSchema:prefix>
+            <pdfaSchema:property>
+              <rdf:Seq>
+                <rdf:li rdf:parseType="Resource">
+                  <pdfaProperty:name>DocumentFileName</pdfaProperty:name>
+                  <pdfaProperty:valueType>Text</pdfaProperty:valueType>
+                  <pdfaProperty:category>external</pdfaProperty:category>
+                  <pdfaProperty:description>The name of the embedded XML document</pdfaProperty:description>
+                </rdf:li>
+                <rdf:li rdf:parseType="Resource">
+                  <pdfaProperty:name>DocumentType</pdfaProperty:name>
+                  <pdfaProperty:valueType>Text</pdfaProperty:valueType>
+                  <pdfaProperty:category>external</pdfaProperty:category>
+                  <pdfaProperty:description>The type of the hybrid document in capital letters, e.g. INVOICE or ORDER</pdfaProperty:description>
+                </rdf:li>
+                <rdf:li rdf:parseType="Resource">
+                  <pdfaProperty:name>Version</pdfaProperty:name>
+                  <pdfaProperty:valueType>Text</pdfaProperty:valueType>
+                  <pdfaProperty:category>external</pdfaProperty:category>
+                  <pdfaProperty:description>The actual version of the standard applying to the embedded XML document</pdfaProperty:description>
+                </rdf:li>
+                <rdf:li rdf:parseType="Resource">
+                  <pdfaProperty:name>ConformanceLevel</pdfaProperty:name>
+                  <pdfaProperty:valueType>Text</pdfaProperty:valueType>
+                  <pdfaProperty:category>external</pdfaProperty:category>
+                  <pdfaProperty:description>The conformance level of the embedded XML document</pdfaProperty:description>
+                </rdf:li>
+              </rdf:Seq>
+            </pdfaSchema:property>
+          </rdf:li>
+        </rdf:Bag>
+      </pdfaExtension:schemas>
+    </rdf:Description>
+    <rdf:Description rdf:about=""
+        xmlns:fx="urn:factur-x:pdfa:CrossIndustryDocument:invoice:1p0#">
+      <fx:DocumentType>INVOICE</fx:DocumentType>
+      <fx:DocumentFileName>factur-x.xml</fx:DocumentFileName>
+      <fx:Version>1.0</fx:Version>
+      <fx:ConformanceLevel>EN 16931</fx:ConformanceLevel>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#;
+
+impl Invoice {
+    /// Generate the UN/CEFACT Cross Industry Invoice XML of this invoice, following the EN 16931 profile
+    /// of Factur-X/ZUGFeRD.
+    /// Amounts are rounded using the invoice's [crate::Rounding].
+    ///
+    /// # Errors
+    ///
+    /// If the invoice lacks information required for e-invoices, e.g. the country codes of the parties,
+    /// or if its totals do not equal the sums of its lines and VAT breakdown
+    pub fn to_cii(&self) -> Result<String, Error> {
+        let mut issues = Vec::new();
+        self.validate_e_invoice_at("", &mut issues);
+        if !issues.is_empty() {
+            return Err(Error::Validation(issues));
+        }
+
+        let mut xml = XmlWriter::new();
+        xml.start(
+            "rsm:CrossIndustryInvoice",
+            &[
+                (
+                    "xmlns:rsm",
+                    "urn:un:unece:uncefact:data:standard:CrossIndustryInvoice:100",
+                ),
+                (
+                    "xmlns:ram",
+                    "urn:un:unece:uncefact:data:standard:ReusableAggregateBusinessInformationEntity:100",
+                ),
+                (
+                    "xmlns:udt",
+                    "urn:un:unece:uncefact:data:standard:UnqualifiedDataType:100",
+                ),
+            ],
+        );
+
+        xml.start("rsm:ExchangedDocumentContext", &[])
+            .start("ram:GuidelineSpecifiedDocumentContextParameter", &[])
+            .text("ram:ID", &[], EN_16931_GUIDELINE)
+            .end()
+            .end();
+
+        xml.start("rsm:ExchangedDocument", &[])
+            .text("ram:ID", &[], &self.invoice_id)
+            .text("ram:TypeCode", &[], COMMERCIAL_INVOICE);
+        write_date(&mut xml, "ram:IssueDateTime", self.invoice_date);
+        if let Some(note) = &self.note {
+            xml.start("ram:IncludedNote", &[])
+                .text("ram:Content", &[], note)
+                .end();
+        }
+        xml.end();
+
+        xml.start("rsm:SupplyChainTradeTransaction", &[]);
+        for (idx, item) in self.items.iter().enumerate() {
+            self.write_cii_line(&mut xml, idx + 1, item);
+        }
+        self.write_cii_agreement(&mut xml);
+        xml.start("ram:ApplicableHeaderTradeDelivery", &[]).end();
+        self.write_cii_settlement(&mut xml);
+        xml.end();
+
+        xml.end();
+        Ok(xml.finish())
+    }
+
+    /// Embed the CII XML of this invoice in the document, making it a Factur-X invoice.
+    ///
+    /// # Errors
+    ///
+    /// If the document is not written as PDF/A-3b, the invoice lacks information required for e-invoices
+    /// or a JNI error occurs
+    pub(crate) fn embed_factur_x<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        if target.pdf_a != Some(PdfAConformanceLevel::PdfA3B) {
+            return Err(Error::FacturXRequiresPdfA3);
+        }
+
+        let xml = self.to_cii()?;
+        let pdf = target.document.get_pdf_document(env)?;
+        pdf.add_associated_file(
+            &AssociatedFile {
+                name: FACTUR_X_FILE_NAME,
+                description: "Factur-X invoice",
+                mime_type: "text/xml",
+                relationship: "Alternative",
+                bytes: xml.as_bytes(),
+            },
+            env,
+        )?;
+        pdf.append_xmp_metadata(FACTUR_X_XMP, env)?;
+
+        Ok(())
+    }
+
+    /// Write an invoice line
+    fn write_cii_line(&self, xml: &mut XmlWriter, line: usize, item: &InvoiceItem) {
+        xml.start("ram:IncludedSupplyChainTradeLineItem", &[])
+            .start("ram:AssociatedDocumentLineDocument", &[])
+            .text("ram:LineID", &[], &line.to_string())
+            .end();
+
+        xml.start("ram:SpecifiedTradeProduct", &[])
+            .text("ram:SellerAssignedID", &[], &item.identifier)
+            .text("ram:Name", &[], &item.description)
+            .end();
+
+        xml.start("ram:SpecifiedLineTradeAgreement", &[]);
+        if !item.discount_percentage.is_zero() {
+            let discount = item.price_per_unit - item.subtotal_price_per_unit;
+            xml.start("ram:GrossPriceProductTradePrice", &[])
+                .text(
+                    "ram:ChargeAmount",
+                    &[],
+                    &item.price_per_unit.normalize().to_string(),
+                )
+                .start("ram:AppliedTradeAllowanceCharge", &[])
+                .start("ram:ChargeIndicator", &[])
+                .text("udt:Indicator", &[], "false")
+                .end()
+                .text("ram:ActualAmount", &[], &discount.normalize().to_string())
+                .end()
+                .end();
+        }
+        xml.start("ram:NetPriceProductTradePrice", &[])
+            .text(
+                "ram:ChargeAmount",
+                &[],
+                &item.subtotal_price_per_unit.normalize().to_string(),
+            )
+            .end()
+            .end();
+
+        xml.start("ram:SpecifiedLineTradeDelivery", &[])
+            .text(
+                "ram:BilledQuantity",
+                &[("unitCode", UNIT_CODE)],
+                &item.quantity.to_string(),
+            )
+            .end();
+
+        xml.start("ram:SpecifiedLineTradeSettlement", &[])
+            .start("ram:ApplicableTradeTax", &[])
+            .text("ram:TypeCode", &[], "VAT")
+            .text("ram:CategoryCode", &[], item.vat_rate.category_code())
+            .text(
+                "ram:RateApplicablePercent",
+                &[],
+                &item.vat_rate.percentage().normalize().to_string(),
+            )
+            .end()
+            .start("ram:SpecifiedTradeSettlementLineMonetarySummation", &[])
+            .text(
+                "ram:LineTotalAmount",
+                &[],
+                &self.e_invoice_amount(item.total_price),
+            )
+            .end()
+            .end();
+
+        xml.end();
+    }
+
+    /// Write the parties and references of the invoice
+    fn write_cii_agreement(&self, xml: &mut XmlWriter) {
+        xml.start("ram:ApplicableHeaderTradeAgreement", &[]);
+        if !self.reference.trim().is_empty() {
+            xml.text("ram:BuyerReference", &[], &self.reference);
+        }
+        write_party(xml, "ram:SellerTradeParty", &self.header.seller);
+        write_party(xml, "ram:BuyerTradeParty", &self.header.addressed_to);
+        xml.start("ram:BuyerOrderReferencedDocument", &[])
+            .text("ram:IssuerAssignedID", &[], &self.order_id)
+            .end();
+        xml.end();
+    }
+
+    /// Write the currency, payment means, VAT breakdown, payment terms and totals of the invoice
+    fn write_cii_settlement(&self, xml: &mut XmlWriter) {
+        let currency = self.currency.code();
+
+        let bank_account = self.header.seller.bank_account.as_ref();
+        xml.start("ram:ApplicableHeaderTradeSettlement", &[]);
+        if bank_account.is_some() {
+            xml.text("ram:PaymentReference", &[], &self.invoice_id);
+        }
+        xml.text("ram:InvoiceCurrencyCode", &[], currency);
+
+        if let Some(bank_account) = bank_account {
+            xml.start("ram:SpecifiedTradeSettlementPaymentMeans", &[])
+                .text("ram:TypeCode", &[], SEPA_CREDIT_TRANSFER)
+                .start("ram:PayeePartyCreditorFinancialAccount", &[])
+                .text("ram:IBANID", &[], &bank_account.normalized_iban())
+                .end();
+            if let Some(bic) = &bank_account.bic {
+                xml.start("ram:PayeeSpecifiedCreditorFinancialInstitution", &[])
+                    .text("ram:BICID", &[], bic)
+                    .end();
+            }
+            xml.end();
+        }
+
+        for summary in self.vat_breakdown() {
+            xml.start("ram:ApplicableTradeTax", &[])
+                .text(
+                    "ram:CalculatedAmount",
+                    &[],
+                    &self.e_invoice_amount(summary.vat_amount),
+                )
+                .text("ram:TypeCode", &[], "VAT");
+            if let Some(reason) = summary.rate.exemption_reason() {
+                xml.text("ram:ExemptionReason", &[], reason);
+            }
+            xml.text(
+                "ram:BasisAmount",
+                &[],
+                &self.e_invoice_amount(summary.base_amount),
+            )
+            .text("ram:CategoryCode", &[], summary.rate.category_code())
+            .text(
+                "ram:RateApplicablePercent",
+                &[],
+                &summary.rate.percentage().normalize().to_string(),
+            )
+            .end();
+        }
+
+        if let Some(due_date) = self.expiration_date() {
+            xml.start("ram:SpecifiedTradePaymentTerms", &[]);
+            write_date(xml, "ram:DueDateDateTime", due_date);
+            xml.end();
+        }
+
+        // The totals equal the sums of the lines and the VAT breakdown, see [Invoice::validate_e_invoice_at]
+        let totals = &self.totals;
+        xml.start("ram:SpecifiedTradeSettlementHeaderMonetarySummation", &[])
+            .text(
+                "ram:LineTotalAmount",
+                &[],
+                &self.e_invoice_line_total().to_string(),
+            )
+            .text(
+                "ram:TaxBasisTotalAmount",
+                &[],
+                &self.e_invoice_amount(totals.total_excluding_vat),
+            )
+            .text(
+                "ram:TaxTotalAmount",
+                &[("currencyID", currency)],
+                &self.e_invoice_amount(totals.total_vat),
+            )
+            .text(
+                "ram:GrandTotalAmount",
+                &[],
+                &self.e_invoice_amount(totals.total_including_vat),
+            )
+            .text(
+                "ram:DuePayableAmount",
+                &[],
+                &self.e_invoice_amount(totals.total_including_vat),
+            )
+            .end();
+
+        xml.end();
+    }
+}
+
+/// Write a trading party, which must have a country code
+fn write_party(xml: &mut XmlWriter, name: &str, party: &AddressableParty) {
+    let address = &party.address;
+
+    xml.start(name, &[]).text("ram:Name", &[], &party.name);
+    if let Some(legal_registration) = &party.legal_registration {
+        xml.start("ram:SpecifiedLegalOrganization", &[])
+            .text(
+                "ram:ID",
+                &[("schemeID", &legal_registration.scheme)],
+                &legal_registration.identifier,
+            )
+            .end();
+    }
+    xml.start("ram:PostalTradeAddress", &[])
+        .text("ram:PostcodeCode", &[], &address.zipcode)
+        .text(
+            "ram:LineOne",
+            &[],
+            &format!("{} {}", address.street, address.number),
+        )
+        .text("ram:CityName", &[], &address.city)
+        .text(
+            "ram:CountryID",
+            &[],
+            address.country_code.as_deref().unwrap_or_default(),
+        )
+        .end();
+    if let Some(vat_number) = &party.vat_number {
+        xml.start("ram:SpecifiedTaxRegistration", &[])
+            .text("ram:ID", &[("schemeID", "VA")], vat_number)
+            .end();
+    }
+    xml.end();
+}
+
+/// Write a date in the `YYYYMMDD` format
+fn write_date(xml: &mut XmlWriter, name: &str, date: NaiveDate) {
+    xml.start(name, &[])
+        .text(
+            "udt:DateTimeString",
+            &[("format", "102")],
+            &date.format("%Y%m%d").to_string(),
+        )
+        .end();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invoice::tests::sample_invoice;
+    use crate::Error;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn writes_sample_invoice() {
+        let xml = sample_invoice().to_cii().unwrap();
+
+        for expected in [
+            "<ram:ID>urn:cen.eu:en16931:2017</ram:ID>",
+            "<rsm:ExchangedDocument>\n    <ram:ID>230307</ram:ID>\n    <ram:TypeCode>380</ram:TypeCode>",
+            "<udt:DateTimeString format=\"102\">20230619</udt:DateTimeString>",
+            "<ram:BuyerReference>PO-7</ram:BuyerReference>",
+            "<ram:Name>Smith &amp; Sons</ram:Name>",
+            "<ram:SpecifiedLegalOrganization>\n          \
+             <ram:ID schemeID=\"0106\">67223370</ram:ID>\n        </ram:SpecifiedLegalOrganization>",
+            "<ram:ID schemeID=\"VA\">NL856883566B01</ram:ID>",
+            "<ram:BilledQuantity unitCode=\"C62\">2</ram:BilledQuantity>",
+            "<ram:LineTotalAmount>119.90</ram:LineTotalAmount>",
+            "<ram:ApplicableHeaderTradeSettlement>\n      <ram:PaymentReference>230307</ram:PaymentReference>\n      \
+             <ram:InvoiceCurrencyCode>EUR</ram:InvoiceCurrencyCode>\n      \
+             <ram:SpecifiedTradeSettlementPaymentMeans>\n        <ram:TypeCode>58</ram:TypeCode>\n        \
+             <ram:PayeePartyCreditorFinancialAccount>\n          <ram:IBANID>NL75INGB0007538383</ram:IBANID>\n        \
+             </ram:PayeePartyCreditorFinancialAccount>\n        \
+             <ram:PayeeSpecifiedCreditorFinancialInstitution>\n          <ram:BICID>INGBNL2A</ram:BICID>\n        \
+             </ram:PayeeSpecifiedCreditorFinancialInstitution>\n      </ram:SpecifiedTradeSettlementPaymentMeans>\n      \
+             <ram:ApplicableTradeTax>",
+            "<ram:ApplicableTradeTax>\n        <ram:CalculatedAmount>25.18</ram:CalculatedAmount>\n        \
+             <ram:TypeCode>VAT</ram:TypeCode>\n        <ram:BasisAmount>119.90</ram:BasisAmount>\n        \
+             <ram:CategoryCode>S</ram:CategoryCode>\n        <ram:RateApplicablePercent>21</ram:RateApplicablePercent>",
+            "<ram:CalculatedAmount>0.90</ram:CalculatedAmount>",
+            "<udt:DateTimeString format=\"102\">20230719</udt:DateTimeString>",
+            "<ram:SpecifiedTradeSettlementHeaderMonetarySummation>\n        \
+             <ram:LineTotalAmount>129.85</ram:LineTotalAmount>\n        \
+             <ram:TaxBasisTotalAmount>129.85</ram:TaxBasisTotalAmount>\n        \
+             <ram:TaxTotalAmount currencyID=\"EUR\">26.08</ram:TaxTotalAmount>\n        \
+             <ram:GrandTotalAmount>155.93</ram:GrandTotalAmount>\n        \
+             <ram:DuePayableAmount>155.93</ram:DuePayableAmount>\n      \
+             </ram:SpecifiedTradeSettlementHeaderMonetarySummation>",
+        ] {
+            assert!(xml.contains(expected), "missing {expected:?} in\n{xml}");
+        }
+        assert!(xml.ends_with("</rsm:CrossIndustryInvoice>\n"));
+    }
+
+    #[test]
+    fn rejects_totals_which_do_not_add_up() {
+        let mut invoice = sample_invoice();
+        invoice.totals.total_excluding_vat = dec!(129.86);
+
+        let Err(Error::Validation(issues)) = invoice.to_cii() else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(issues[0].path, "totals.total_excluding_vat");
+    }
+}
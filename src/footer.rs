@@ -1,7 +1,11 @@
-use crate::{Error, PdfRenderable, RenderTarget};
+use crate::layout::{CanvasExt, DocumentExt};
+use crate::validate::{field_path, require_non_empty};
+use crate::{Error, RenderTarget, Validate, ValidationIssue};
 use colors_transform::{Color as _, Rgb};
 use itext::itext::kernel::{Color, ColorConstant};
-use itext::itext::layout::{Border, Cell, ElementPropertyContainer, Paragraph, Table};
+use itext::itext::layout::{
+    Border, Canvas, Cell, ElementPropertyContainer, Paragraph, RootElement, Table,
+};
 use jni::JNIEnv;
 
 /// A document's footer
@@ -18,35 +22,125 @@ pub struct Footer {
 /// The maximum amount of fields per row in the footer
 const MAX_FIELD_H: usize = 4;
 
-impl PdfRenderable for Footer {
-    fn render<'a>(&self, target: &RenderTarget<'a>, env: &mut JNIEnv<'a>) -> Result<(), Error> {
-        let footer = Table::new(&[1.0; MAX_FIELD_H], env)?;
+/// The space between the footer and the content above it
+const FOOTER_GAP: f32 = 10.0;
+
+impl Validate for Footer {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        require_non_empty(&self.company_name, path, "company_name", issues);
+
+        if Rgb::from_hex_str(&self.company_name_color).is_err() {
+            issues.push(ValidationIssue {
+                path: field_path(path, "company_name_color"),
+                message: format!("'{}' is not a hexadecimal color", self.company_name_color),
+            });
+        }
+    }
+}
+
+impl Footer {
+    /// Reserve room for the footer at the bottom of every page by increasing the document's bottom margin.
+    /// Called by [RenderTarget::set_footer], before any content is added to the document.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub(crate) fn reserve_space<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
         let doc = &target.document;
+        let bottom = doc.get_bottom_margin(env)? + self.height(target, env)? + FOOTER_GAP;
+
+        doc.set_margins(
+            doc.get_top_margin(env)?,
+            doc.get_right_margin(env)?,
+            bottom,
+            doc.get_left_margin(env)?,
+            env,
+        )?;
+
+        Ok(())
+    }
+
+    /// Draw the footer on every page of the document, in the room reserved by [Footer::reserve_space].
+    /// Called by [RenderTarget::finish], once all content has been added to the document.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub(crate) fn draw<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let doc = &target.document;
+        let pdf = doc.get_pdf_document(env)?;
+
+        let left = doc.get_left_margin(env)?;
+        let width = self.width(target, env)?;
+        let bottom = doc.get_bottom_margin(env)? - self.height(target, env)? - FOOTER_GAP;
+
+        for page_num in 1..=pdf.get_number_of_pages(env)? {
+            let page = pdf.get_page(page_num, env)?;
+            let canvas = Canvas::new_from_page(&page, &page.get_page_size(env)?, env)?;
+            target.apply_fonts(&canvas, env)?;
+
+            let footer = self.new_table(env)?;
+            footer.set_fixed_position(left, bottom, width, env)?;
+            canvas.add(footer, env)?;
+            canvas.close(env)?;
+        }
+
+        Ok(())
+    }
+
+    /// The width of the footer, i.e. the width of the page between the margins.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn width<'a>(&self, target: &RenderTarget<'a>, env: &mut JNIEnv<'a>) -> Result<f32, Error> {
+        let doc = &target.document;
+        let page_width = doc
+            .get_pdf_document(env)?
+            .get_default_page_size(env)?
+            .get_width(env)?;
+
+        Ok(page_width - doc.get_left_margin(env)? - doc.get_right_margin(env)?)
+    }
+
+    /// The height of the footer.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn height<'a>(&self, target: &RenderTarget<'a>, env: &mut JNIEnv<'a>) -> Result<f32, Error> {
+        let width = self.width(target, env)?;
+        let footer = self.new_table(env)?;
+        Ok(target.document.measure_height(&footer, width, env)?)
+    }
+
+    /// Create the table containing the footer.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn new_table<'a>(&self, env: &mut JNIEnv<'a>) -> Result<Table<'a>, Error> {
+        let footer = Table::new(&[1.0; MAX_FIELD_H], env)?;
 
         footer
             .set_font_size(7.0, env)?
             .set_font_color(&Color::from_constant(ColorConstant::LightGray, env)?, env)?
-            .set_fixed_position(
-                doc.get_left_margin(env)?,
-                doc.get_bottom_margin(env)?,
-                doc.get_pdf_document(env)?
-                    .get_default_page_size(env)?
-                    .get_width(env)?
-                    - doc.get_left_margin(env)?
-                    - doc.get_right_margin(env)?,
-                env,
-            )?
             .use_all_available_width(env)?;
 
         self.render_company_name(&footer, env)?;
         self.render_footer_fields(&footer, env)?;
 
-        target.document.add(footer, env)?;
-        Ok(())
+        Ok(footer)
     }
-}
 
-impl Footer {
     /// Render all fields of the footer to the table.
     ///
     /// # Errors
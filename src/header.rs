@@ -1,8 +1,10 @@
-use crate::{Error, PdfRenderable, RenderTarget};
+use crate::layout::{CanvasExt, DocumentExt, ElementPropertyContainerExt};
+use crate::validate::{field_path, require_non_empty};
+use crate::{Error, Label, PdfRenderable, RenderTarget, Validate, ValidationIssue};
 use itext::itext::io::ImageData;
 use itext::itext::layout::{
-    BlockElement, Border, Cell, ElementPropertyContainer, HorizontalAlignment, Image, Paragraph,
-    Table, TextAlignment,
+    BlockElement, Border, Canvas, Cell, ElementPropertyContainer, HorizontalAlignment, Image,
+    Paragraph, RootElement, Table, TextAlignment,
 };
 use itext::java::{BufferedImage, ByteArrayInputStream};
 use itext::javax::ImageInputStream;
@@ -19,6 +21,8 @@ pub struct Header {
     pub addressed_to: AddressableParty,
     /// The seller
     pub seller: AddressableParty,
+    /// Repeat the logo in a strip at the top of every following page
+    pub logo_on_every_page: bool,
 }
 
 /// An entity which has an address
@@ -32,6 +36,40 @@ pub struct AddressableParty {
     pub address: Address,
     /// The VAT number of the company
     pub vat_number: Option<String>,
+    /// The address at which the company receives e-invoices, e.g. its Peppol ID
+    pub electronic_address: Option<ElectronicAddress>,
+    /// The identifier of the company in a trade register, e.g. its KvK number
+    pub legal_registration: Option<LegalRegistration>,
+    /// The bank account payments are made to
+    pub bank_account: Option<BankAccount>,
+}
+
+/// A bank account in the SEPA area
+#[derive(Debug, Clone)]
+pub struct BankAccount {
+    /// The International Bank Account Number, e.g. `NL75 INGB 0007 5383 83`.
+    /// Spaces are ignored.
+    pub iban: String,
+    /// The Business Identifier Code of the bank, e.g. `INGBNL2A`
+    pub bic: Option<String>,
+}
+
+/// An address at which an entity receives electronic documents
+#[derive(Debug, Clone)]
+pub struct ElectronicAddress {
+    /// The Electronic Address Scheme (EAS) code of the identifier, e.g. `0106` for a Dutch KvK number
+    pub scheme: String,
+    /// The identifier within the scheme
+    pub identifier: String,
+}
+
+/// The registration of a company as legal entity
+#[derive(Debug, Clone)]
+pub struct LegalRegistration {
+    /// The ISO/IEC 6523 code of the register, e.g. `0106` for the Dutch Chamber of Commerce (KvK)
+    pub scheme: String,
+    /// The identifier within the register
+    pub identifier: String,
 }
 
 /// An address
@@ -47,6 +85,9 @@ pub struct Address {
     pub city: String,
     /// The country
     pub country: String,
+    /// The ISO 3166-1 alpha-2 code of the country, e.g. `NL`.
+    /// Required for e-invoices.
+    pub country_code: Option<String>,
 }
 
 impl PdfRenderable for Header {
@@ -54,24 +95,185 @@ impl PdfRenderable for Header {
         let table = Table::new(&[8.0, 2.0], env)?;
         table.use_all_available_width(env)?.start_new_row(env)?;
 
-        self.render_document_info(&table, env)?;
+        self.render_document_info(&table, target, env)?;
         self.render_logo(&table, env)?;
 
         table.start_new_row(env)?;
-        self.render_document_label(&table, env)?;
+        self.render_document_label(&table, target, env)?;
 
         target.document.add(table, env)?;
+
+        if self.logo_on_every_page {
+            self.reserve_logo_strip_space(target, env)?;
+        }
         Ok(())
     }
 }
 
+impl Validate for Header {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        require_non_empty(&self.label, path, "label", issues);
+
+        if self.logo.is_empty() {
+            issues.push(ValidationIssue {
+                path: field_path(path, "logo"),
+                message: "must not be empty".to_string(),
+            });
+        } else if !is_supported_image(&self.logo) {
+            issues.push(ValidationIssue {
+                path: field_path(path, "logo"),
+                message: "is not a PNG, JPEG, GIF, BMP or TIFF image".to_string(),
+            });
+        }
+
+        self.addressed_to
+            .validate_at(&field_path(path, "addressed_to"), issues);
+        self.seller.validate_at(&field_path(path, "seller"), issues);
+    }
+}
+
+impl Validate for AddressableParty {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        require_non_empty(&self.name, path, "name", issues);
+        self.address
+            .validate_at(&field_path(path, "address"), issues);
+
+        if let Some(electronic_address) = &self.electronic_address {
+            let address_path = field_path(path, "electronic_address");
+            require_non_empty(&electronic_address.scheme, &address_path, "scheme", issues);
+            require_non_empty(
+                &electronic_address.identifier,
+                &address_path,
+                "identifier",
+                issues,
+            );
+        }
+
+        if let Some(legal_registration) = &self.legal_registration {
+            let registration_path = field_path(path, "legal_registration");
+            require_non_empty(
+                &legal_registration.scheme,
+                &registration_path,
+                "scheme",
+                issues,
+            );
+            require_non_empty(
+                &legal_registration.identifier,
+                &registration_path,
+                "identifier",
+                issues,
+            );
+        }
+
+        if let Some(bank_account) = &self.bank_account {
+            bank_account.validate_at(&field_path(path, "bank_account"), issues);
+        }
+    }
+}
+
+impl BankAccount {
+    /// The IBAN in its electronic format: without spaces and in uppercase
+    pub(crate) fn normalized_iban(&self) -> String {
+        self.iban.replace(' ', "").to_ascii_uppercase()
+    }
+}
+
+/// Whether an IBAN in electronic format is well-formed: a country code, two check digits which
+/// pass the ISO 7064 MOD 97-10 check and the account number
+fn is_valid_iban(iban: &str) -> bool {
+    let bytes = iban.as_bytes();
+    let well_formed = (15..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..].iter().all(u8::is_ascii_alphanumeric);
+    if !well_formed {
+        return false;
+    }
+
+    // The country code and check digits are moved to the end and letters are replaced by 10 to 35,
+    // after which the number modulo 97 must be 1
+    let remainder = bytes[4..]
+        .iter()
+        .chain(&bytes[..4])
+        .fold(0u32, |remainder, &b| match b {
+            b'0'..=b'9' => (remainder * 10 + u32::from(b - b'0')) % 97,
+            _ => (remainder * 100 + u32::from(b - b'A') + 10) % 97,
+        });
+    remainder == 1
+}
+
+impl Validate for BankAccount {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        if !is_valid_iban(&self.normalized_iban()) {
+            issues.push(ValidationIssue {
+                path: field_path(path, "iban"),
+                message: format!("{} is not an IBAN", self.iban),
+            });
+        }
+
+        if let Some(bic) = &self.bic {
+            if ![8, 11].contains(&bic.len()) || !bic.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                issues.push(ValidationIssue {
+                    path: field_path(path, "bic"),
+                    message: format!("{bic} is not a BIC"),
+                });
+            }
+        }
+    }
+}
+
+impl Validate for Address {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        require_non_empty(&self.street, path, "street", issues);
+        require_non_empty(&self.number, path, "number", issues);
+        require_non_empty(&self.zipcode, path, "zipcode", issues);
+        require_non_empty(&self.city, path, "city", issues);
+        require_non_empty(&self.country, path, "country", issues);
+
+        if let Some(country_code) = &self.country_code {
+            if country_code.len() != 2 || !country_code.bytes().all(|b| b.is_ascii_uppercase()) {
+                issues.push(ValidationIssue {
+                    path: field_path(path, "country_code"),
+                    message: format!("{country_code} is not an ISO 3166-1 alpha-2 code"),
+                });
+            }
+        }
+    }
+}
+
+/// Magic bytes of the image formats which can be used as logo
+const IMAGE_SIGNATURES: &[&[u8]] = &[
+    // PNG
+    b"\x89PNG\r\n\x1a\n",
+    // JPEG
+    b"\xff\xd8\xff",
+    // GIF
+    b"GIF87a",
+    b"GIF89a",
+    // BMP
+    b"BM",
+    // TIFF, little and big endian
+    b"II*\0",
+    b"MM\0*",
+];
+
+/// Whether the bytes start with the signature of a supported image format.
+fn is_supported_image(bytes: &[u8]) -> bool {
+    IMAGE_SIGNATURES
+        .iter()
+        .any(|signature| bytes.starts_with(signature))
+}
+
 /// The height of the logo on the document.
 /// The width will automatically be adjusted to
 /// preserve aspect ration.
 const LOGO_HEIGHT: f32 = 100.0;
 
-/// The prefix to put before the name of the department.
-const DEPARTMENT_PREFIX: &str = "T.a.v";
+/// The height of the logo in the strip at the top of every following page
+const LOGO_STRIP_HEIGHT: f32 = 30.0;
+
+/// The space between the logo strip and the content below it
+const LOGO_STRIP_GAP: f32 = 10.0;
 
 impl Header {
     /// Calculate the width and height of the logo on the document.
@@ -91,6 +293,74 @@ impl Header {
         Ok((logo_width, LOGO_HEIGHT))
     }
 
+    /// Reserve room for the logo strip at the top of the pages following the current one,
+    /// by increasing the document's top margin.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn reserve_logo_strip_space<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let doc = &target.document;
+        let top = doc.get_top_margin(env)? + LOGO_STRIP_HEIGHT + LOGO_STRIP_GAP;
+
+        doc.set_margins(
+            top,
+            doc.get_right_margin(env)?,
+            doc.get_bottom_margin(env)?,
+            doc.get_left_margin(env)?,
+            env,
+        )?;
+
+        Ok(())
+    }
+
+    /// Draw the logo strip on every page but the first, which contains the full header.
+    /// Does nothing unless [Header::logo_on_every_page] is set.
+    /// All content must have been added to the document.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub fn render_logo_strip<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        if !self.logo_on_every_page {
+            return Ok(());
+        }
+
+        let doc = &target.document;
+        let pdf = doc.get_pdf_document(env)?;
+
+        let (logo_width, logo_height) = self.calculate_logo_size(env)?;
+        let width = logo_width * LOGO_STRIP_HEIGHT / logo_height;
+        let right = doc.get_right_margin(env)?;
+        // The top margin includes the strip, see [Header::reserve_logo_strip_space]
+        let top_margin = doc.get_top_margin(env)?;
+
+        for page_num in 2..=pdf.get_number_of_pages(env)? {
+            let page = pdf.get_page(page_num, env)?;
+            let page_size = page.get_page_size(env)?;
+            let left = page_size.get_right(env)? - right - width;
+            let bottom = page_size.get_top(env)? - top_margin + LOGO_STRIP_GAP;
+
+            let canvas = Canvas::new_from_page(&page, &page_size, env)?;
+            let logo = Image::new(ImageData::new(env, &self.logo)?, env)?;
+            logo.set_width(width, env)?
+                .set_height(LOGO_STRIP_HEIGHT, env)?
+                .set_fixed_position(left, bottom, width, env)?;
+            canvas.add_image(&logo, env)?;
+            canvas.close(env)?;
+        }
+
+        Ok(())
+    }
+
     /// Render the document label to the header table
     ///
     /// # Errors
@@ -99,6 +369,7 @@ impl Header {
     fn render_document_label<'a>(
         &self,
         table: &Table<'a>,
+        target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
         table
@@ -114,7 +385,7 @@ impl Header {
                 Cell::new(env)?
                     .add(
                         Paragraph::new_with_text(&self.label, env)?
-                            .set_bold(env)?
+                            .set_bold_face(target.faces, env)?
                             .set_text_alignment(TextAlignment::Right, env)?
                             .set_font_size(24.0, env)?,
                         env,
@@ -159,21 +430,27 @@ impl Header {
     fn render_document_info<'a>(
         &self,
         table: &Table<'a>,
+        target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
+        let translations = &target.translations;
         let cell = Cell::new(env)?;
         cell.set_border(Border::NoBorder, env)?;
 
         // Name
         cell.add(
-            Paragraph::new_with_text(&self.addressed_to.name, env)?.set_bold(env)?,
+            Paragraph::new_with_text(&self.addressed_to.name, env)?
+                .set_bold_face(target.faces, env)?,
             env,
         )?;
 
         // Department
         if let Some(department) = &self.addressed_to.department {
             cell.add(
-                &Paragraph::new_with_text(&format!("{DEPARTMENT_PREFIX} {department}"), env)?,
+                &Paragraph::new_with_text(
+                    &format!("{} {department}", translations.get(Label::DepartmentPrefix)),
+                    env,
+                )?,
                 env,
             )?;
         }
@@ -227,7 +504,7 @@ impl Header {
                 env,
             )?
             .set_font_size(9.0, env)?
-            .set_italic(env)?,
+            .set_italic_face(target.faces, env)?,
             env,
         )?;
 
@@ -235,3 +512,54 @@ impl Header {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iban_issues(iban: &str) -> Vec<ValidationIssue> {
+        BankAccount {
+            iban: iban.to_string(),
+            bic: None,
+        }
+        .validate()
+    }
+
+    #[test]
+    fn accepts_valid_ibans() {
+        for iban in [
+            "NL75 INGB 0007 5383 83",
+            "NL75INGB0007538383",
+            "nl75 ingb 0007 5383 83",
+            "GB82 WEST 1234 5698 7654 32",
+            "DE89 3704 0044 0532 0130 00",
+            "BE68 5390 0754 7034",
+        ] {
+            assert_eq!(iban_issues(iban), Vec::new(), "{iban}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_ibans() {
+        for iban in [
+            // Wrong check digits
+            "NL76 INGB 0007 5383 83",
+            // Swapped digits
+            "NL75 INGB 0007 5383 38",
+            // No country code
+            "75 INGB 0007 5383 83",
+            "1275 INGB 0007 5383 83",
+            // No check digits
+            "NLAB INGB 0007 5383 83",
+            // Too short and too long
+            "NL75 INGB 0007",
+            "NL75 INGB 0007 5383 8300 0000 0000 0000 0",
+            "NL75 INGB-0007 5383 83",
+            "",
+        ] {
+            let issues = iban_issues(iban);
+            assert_eq!(issues.len(), 1, "{iban}");
+            assert_eq!(issues[0].path, "iban");
+        }
+    }
+}
@@ -1,11 +1,20 @@
 use crate::footer::Footer;
 use crate::header::Header;
-use crate::{Error, PdfRenderable, RenderTarget};
+use crate::layout::{BlockElementExt, DocumentExt, ElementPropertyContainerExt, TableExt};
+pub use crate::money::{Currency, SymbolPlacement};
+use crate::pagination::{add_paginated_table, PaginatedTable};
+use crate::validate::{field_path, index_path, require_non_empty};
+use crate::{
+    Decimal, DocumentMetadata, Error, Label, Locale, NaiveDate, PdfRenderable, RenderTarget,
+    Rounding, Translations, Validate, ValidationIssue,
+};
+use chrono::{Datelike, Days, Months};
 use itext::itext::kernel::ColorConstant;
 use itext::itext::layout::{
-    BlockElement, Border, Cell, Document, ElementPropertyContainer, Paragraph, Table, TextAlignment,
+    BlockElement, Border, Cell, ElementPropertyContainer, Paragraph, Table, TextAlignment,
 };
 use jni::JNIEnv;
+use std::ops::Range;
 
 #[derive(Debug, Clone)]
 pub struct Invoice {
@@ -20,9 +29,10 @@ pub struct Invoice {
     /// The invoice ID
     pub invoice_id: String,
     /// The invoice date
-    pub invoice_date: String,
-    /// Date at which the invoice expires
-    pub expiration_date: String,
+    pub invoice_date: NaiveDate,
+    /// When payment is due.
+    /// The expiration date is derived from this and the invoice date, see [Invoice::expiration_date]
+    pub payment_term: PaymentTerm,
     /// Invoice totals
     pub totals: InvoiceTotals,
     /// Items to be invoiced
@@ -31,11 +41,85 @@ pub struct Invoice {
     pub note: Option<String>,
     /// Currency of the invoice
     pub currency: Currency,
+    /// How amounts are rounded when they are printed or recomputed
+    pub rounding: Rounding,
+    /// If set, the totals and line items are recomputed and
+    /// rendering fails if they do not match the supplied amounts.
+    pub strict_totals: Option<StrictTotals>,
+    /// Where the totals and VAT summary are placed
+    pub totals_placement: TotalsPlacement,
+    /// Embed the invoice as Factur-X/ZUGFeRD XML (EN 16931 profile), see [Invoice::to_cii].
+    /// Requires the document to be written as PDF/A-3b, see [crate::DocumentConfiguration::pdf_a].
+    pub factur_x: bool,
+    /// Print an EPC QR code in the totals area, which customers can scan to pay the invoice,
+    /// see [Invoice::epc_payload]. Requires the seller's bank account and an invoice in euro.
+    pub payment_qr_code: bool,
+}
+
+/// Where the invoice totals and VAT summary are placed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TotalsPlacement {
+    /// Directly after the items and the note
+    #[default]
+    Flow,
+    /// At the bottom of the last page
+    BottomOfLastPage,
 }
 
+/// Settings for verifying the invoice's amounts, see [Invoice::verify_totals]
 #[derive(Debug, Clone)]
-pub enum Currency {
-    Euro,
+pub struct StrictTotals {
+    /// The largest difference allowed between a supplied and a recomputed amount
+    pub tolerance: Decimal,
+}
+
+/// When payment of an invoice is due
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaymentTerm {
+    /// Due a number of days after the invoice date, e.g. `Net(30)`
+    Net(u32),
+    /// Due a number of days after the last day of the invoice date's month, e.g. `EndOfMonth(14)`
+    EndOfMonth(u32),
+    /// Due on a fixed date
+    On(NaiveDate),
+}
+
+impl PaymentTerm {
+    /// The date at which payment is due for an invoice dated `invoice_date`.
+    /// Returns None if the date is out of range.
+    pub fn due_date(&self, invoice_date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Net(days) => invoice_date.checked_add_days(Days::new(u64::from(*days))),
+            Self::EndOfMonth(days) => invoice_date
+                .with_day(1)?
+                .checked_add_months(Months::new(1))?
+                .pred_opt()?
+                .checked_add_days(Days::new(u64::from(*days))),
+            Self::On(date) => Some(*date),
+        }
+    }
+}
+
+/// The VAT rate applied to an item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VatRate {
+    /// A percentage of the price, e.g. `21`, `9` or `0`
+    Percentage(Decimal),
+    /// The item is exempt from VAT
+    Exempt,
+    /// The VAT is reverse-charged to the customer
+    ReverseCharge,
+}
+
+/// The VAT of all items with the same VAT rate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VatSummary {
+    /// The VAT rate
+    pub rate: VatRate,
+    /// The total price excluding VAT of all items with this rate
+    pub base_amount: Decimal,
+    /// The amount of VAT over the base amount
+    pub vat_amount: Decimal,
 }
 
 #[derive(Debug, Clone)]
@@ -47,14 +131,16 @@ pub struct InvoiceItem {
     /// The item quantity
     pub quantity: i32,
     /// The price per unit
-    pub price_per_unit: f32,
+    pub price_per_unit: Decimal,
     /// The discount percentage.
     /// Omitted if all articles lack a discount.
-    pub discount_percentage: f32,
+    pub discount_percentage: Decimal,
     /// The price per unit taking into account the discount
-    pub subtotal_price_per_unit: f32,
+    pub subtotal_price_per_unit: Decimal,
     /// Total price of the item.
-    pub total_price: f32,
+    pub total_price: Decimal,
+    /// The VAT rate applied to the item
+    pub vat_rate: VatRate,
 }
 
 /// Invoice totals.
@@ -62,24 +148,52 @@ pub struct InvoiceItem {
 #[derive(Debug, Clone)]
 pub struct InvoiceTotals {
     /// The total price excluding VAT.
-    pub total_excluding_vat: f32,
+    pub total_excluding_vat: Decimal,
     /// The total amount of VAT.
-    pub total_vat: f32,
+    pub total_vat: Decimal,
     /// The total price to be paid including VAT.
-    pub total_including_vat: f32,
+    pub total_including_vat: Decimal,
 }
 
 impl PdfRenderable for Invoice {
     fn render<'a>(&self, target: &RenderTarget<'a>, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        if let Some(strict) = &self.strict_totals {
+            let mismatches = self.verify_totals(strict);
+            if !mismatches.is_empty() {
+                return Err(Error::Validation(mismatches));
+            }
+        }
+
+        self.render_sections(target, env)
+            .map_err(|e| e.resolve_java_exception(env))
+    }
+}
+
+impl Invoice {
+    /// Render all sections of the invoice.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_sections<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        target.apply_metadata(&self.metadata(), env)?;
+        if self.factur_x {
+            self.embed_factur_x(target, env)?;
+        }
+        target.set_footer(&self.footer, env)?;
         self.header.render(target, env)?;
 
-        self.render_invoice_information(&target.document, env)?;
-        self.render_items_section(&target.document, env)?;
+        self.render_invoice_information(target, env)?;
+        self.render_items_section(target, env)?;
 
         if let Some(note) = &self.note {
             let paragraph = Paragraph::new_with_text(note, env)?;
             paragraph
-                .set_bold(env)?
+                .set_bold_face(target.faces, env)?
                 .set_margin_top(30.0, env)?
                 .set_margin_bottom(30.0, env)?;
             target.document.add(paragraph, env)?;
@@ -87,30 +201,476 @@ impl PdfRenderable for Invoice {
 
         self.render_invoice_totals(&target, env)?;
 
-        self.footer.render(target, env)?;
+        self.header.render_logo_strip(target, env)?;
 
         Ok(())
     }
+
+    /// The metadata describing this invoice, e.g. its title and seller
+    fn metadata(&self) -> DocumentMetadata {
+        DocumentMetadata {
+            title: Some(format!("{} {}", self.header.label, self.invoice_id)),
+            author: Some(self.header.seller.name.clone()),
+            keywords: [&self.invoice_id, &self.order_id, &self.reference]
+                .into_iter()
+                .filter(|keyword| !keyword.is_empty())
+                .cloned()
+                .collect(),
+            ..DocumentMetadata::default()
+        }
+    }
+}
+
+/// The largest difference allowed between the sum of
+/// the totals excluding VAT and VAT, and the total including VAT.
+/// Allows for rounding to whole cents.
+const TOTALS_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+impl Validate for Invoice {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        self.header.validate_at(&field_path(path, "header"), issues);
+        self.footer.validate_at(&field_path(path, "footer"), issues);
+
+        require_non_empty(&self.order_id, path, "order_id", issues);
+        require_non_empty(&self.invoice_id, path, "invoice_id", issues);
+
+        match self.expiration_date() {
+            Some(expiration_date) if expiration_date < self.invoice_date => {
+                issues.push(ValidationIssue {
+                    path: field_path(path, "payment_term"),
+                    message: "must not be due before the invoice date".to_string(),
+                })
+            }
+            Some(_) => {}
+            None => issues.push(ValidationIssue {
+                path: field_path(path, "payment_term"),
+                message: "due date is out of range".to_string(),
+            }),
+        }
+
+        if self.items.is_empty() {
+            issues.push(ValidationIssue {
+                path: field_path(path, "items"),
+                message: "must contain at least one item".to_string(),
+            });
+        }
+
+        let items_path = field_path(path, "items");
+        for (idx, item) in self.items.iter().enumerate() {
+            item.validate_at(&index_path(&items_path, idx), issues);
+        }
+
+        self.totals.validate_at(&field_path(path, "totals"), issues);
+
+        if let Some(strict) = &self.strict_totals {
+            issues.extend(self.verify_totals_at(path, strict));
+        }
+
+        if self.factur_x {
+            self.validate_e_invoice_at(path, issues);
+        }
+
+        if self.payment_qr_code {
+            self.validate_epc_at(path, issues);
+        }
+    }
+}
+
+impl Invoice {
+    /// The date at which the invoice expires, derived from the invoice date and payment term.
+    /// Returns None if the date is out of range.
+    pub fn expiration_date(&self) -> Option<NaiveDate> {
+        self.payment_term.due_date(self.invoice_date)
+    }
+
+    /// Recompute the line items and totals from the prices, quantities and discounts,
+    /// and report every supplied amount which differs more than the configured tolerance.
+    /// Recomputed amounts are rounded using the invoice's [Rounding].
+    /// Returns an empty list if all amounts match.
+    ///
+    /// The following amounts are verified:
+    /// - The subtotal price per unit of every item, taking into account the discount
+    /// - The total price of every item
+    /// - The total excluding VAT, as the sum of all item totals
+    /// - The total VAT, as the sum of the VAT per rate
+    /// - The total including VAT
+    pub fn verify_totals(&self, strict: &StrictTotals) -> Vec<ValidationIssue> {
+        self.verify_totals_at("", strict)
+    }
+
+    /// Compute the base amount and VAT amount for every VAT rate used on the invoice.
+    /// The VAT is computed over the base amount per rate, and rounded using the invoice's [Rounding].
+    ///
+    /// Percentages are ordered from high to low, followed by exempt and reverse-charged items.
+    pub fn vat_breakdown(&self) -> Vec<VatSummary> {
+        let mut breakdown: Vec<VatSummary> = Vec::new();
+        for item in &self.items {
+            match breakdown
+                .iter_mut()
+                .find(|summary| summary.rate == item.vat_rate)
+            {
+                Some(summary) => summary.base_amount += item.total_price,
+                None => breakdown.push(VatSummary {
+                    rate: item.vat_rate.clone(),
+                    base_amount: item.total_price,
+                    vat_amount: Decimal::ZERO,
+                }),
+            }
+        }
+
+        for summary in &mut breakdown {
+            if let VatRate::Percentage(percentage) = summary.rate {
+                summary.vat_amount = self.rounding.round(
+                    summary.base_amount * percentage / Decimal::ONE_HUNDRED,
+                    self.currency.minor_units(),
+                );
+            }
+        }
+
+        breakdown.sort_by(|a, b| match (&a.rate, &b.rate) {
+            (VatRate::Percentage(a), VatRate::Percentage(b)) => b.cmp(a),
+            (a, b) => a.sort_key().cmp(&b.sort_key()),
+        });
+
+        breakdown
+    }
+
+    /// Verify the invoice contains the information required by EN 16931 e-invoices,
+    /// besides the information required for rendering.
+    /// `path` is the path of the invoice within the document.
+    pub(crate) fn validate_e_invoice_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        let header_path = field_path(path, "header");
+        let seller_path = field_path(&header_path, "seller");
+        let addressed_to_path = field_path(&header_path, "addressed_to");
+
+        let parties = [
+            (&seller_path, &self.header.seller),
+            (&addressed_to_path, &self.header.addressed_to),
+        ];
+        for (party_path, party) in parties {
+            if party.address.country_code.is_none() {
+                issues.push(ValidationIssue {
+                    path: field_path(party_path, "address.country_code"),
+                    message: "is required for e-invoices".to_string(),
+                });
+            }
+        }
+
+        if self.header.seller.vat_number.is_none() {
+            issues.push(ValidationIssue {
+                path: field_path(&seller_path, "vat_number"),
+                message: "is required for e-invoices".to_string(),
+            });
+        }
+
+        let reverse_charged = self
+            .items
+            .iter()
+            .any(|item| item.vat_rate == VatRate::ReverseCharge);
+        if reverse_charged && self.header.addressed_to.vat_number.is_none() {
+            issues.push(ValidationIssue {
+                path: field_path(&addressed_to_path, "vat_number"),
+                message: "is required for e-invoices with reverse-charged items".to_string(),
+            });
+        }
+
+        self.validate_e_invoice_lines_at(path, issues);
+        self.validate_e_invoice_totals_at(path, issues);
+    }
+
+    /// Format an amount for e-invoices, rounded to the amount of decimals EN 16931 allows
+    pub(crate) fn e_invoice_amount(&self, amount: Decimal) -> String {
+        self.e_invoice_round(amount).to_string()
+    }
+
+    /// Round an amount to the amount of decimals EN 16931 allows
+    fn e_invoice_round(&self, amount: Decimal) -> Decimal {
+        let decimals = self.currency.minor_units().min(2);
+        self.rounding.round(amount, decimals)
+    }
+
+    /// The sum of the rounded line amounts, as written in e-invoices
+    pub(crate) fn e_invoice_line_total(&self) -> Decimal {
+        self.items
+            .iter()
+            .map(|item| self.e_invoice_round(item.total_price))
+            .sum()
+    }
+
+    /// Verify the amount of every line equals its quantity times its net price,
+    /// as PEPPOL requires (PEPPOL-EN16931-R120). Lines have no allowances or charges of their own,
+    /// the discount is part of the net price.
+    fn validate_e_invoice_lines_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        let items_path = field_path(path, "items");
+        for (idx, item) in self.items.iter().enumerate() {
+            let supplied = self.e_invoice_round(item.total_price);
+            let expected =
+                self.e_invoice_round(Decimal::from(item.quantity) * item.subtotal_price_per_unit);
+            if supplied != expected {
+                issues.push(ValidationIssue {
+                    path: field_path(&index_path(&items_path, idx), "total_price"),
+                    message: format!("must be {expected} for e-invoices, got {supplied}"),
+                });
+            }
+        }
+    }
+
+    /// Verify the totals equal the sums of the lines and the VAT breakdown written in e-invoices,
+    /// as EN 16931 requires (BR-CO-13, BR-CO-14 and BR-CO-15). Unlike [Invoice::verify_totals],
+    /// no tolerance is allowed: receivers reject e-invoices which do not add up.
+    fn validate_e_invoice_totals_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        let line_total = self.e_invoice_line_total();
+        let vat_total: Decimal = self
+            .vat_breakdown()
+            .iter()
+            .map(|summary| self.e_invoice_round(summary.vat_amount))
+            .sum();
+
+        let totals_path = field_path(path, "totals");
+        let totals = &self.totals;
+        let expected = [
+            (
+                "total_excluding_vat",
+                totals.total_excluding_vat,
+                line_total,
+            ),
+            ("total_vat", totals.total_vat, vat_total),
+            (
+                "total_including_vat",
+                totals.total_including_vat,
+                line_total + vat_total,
+            ),
+        ];
+        for (field, supplied, expected) in expected {
+            if self.e_invoice_round(supplied) != expected {
+                issues.push(ValidationIssue {
+                    path: field_path(&totals_path, field),
+                    message: format!("must be {expected} for e-invoices, got {supplied}"),
+                });
+            }
+        }
+    }
+
+    /// Verify the totals, see [Self::verify_totals].
+    /// `path` is the path of the invoice within the document.
+    fn verify_totals_at(&self, path: &str, strict: &StrictTotals) -> Vec<ValidationIssue> {
+        let mut mismatches = Vec::new();
+        let round = |amount: Decimal| self.rounding.round(amount, self.currency.minor_units());
+        let mut compare = |field: String, supplied: Decimal, expected: Decimal| {
+            if (supplied - expected).abs() > strict.tolerance {
+                mismatches.push(ValidationIssue {
+                    path: field,
+                    message: format!("expected {expected}, got {supplied}"),
+                });
+            }
+        };
+
+        let items_path = field_path(path, "items");
+        for (idx, item) in self.items.iter().enumerate() {
+            let item_path = index_path(&items_path, idx);
+
+            let discount = Decimal::ONE - item.discount_percentage / Decimal::ONE_HUNDRED;
+            compare(
+                field_path(&item_path, "subtotal_price_per_unit"),
+                item.subtotal_price_per_unit,
+                round(item.price_per_unit * discount),
+            );
+            compare(
+                field_path(&item_path, "total_price"),
+                item.total_price,
+                round(item.subtotal_price_per_unit * Decimal::from(item.quantity)),
+            );
+        }
+
+        let totals_path = field_path(path, "totals");
+        let totals = &self.totals;
+        compare(
+            field_path(&totals_path, "total_excluding_vat"),
+            totals.total_excluding_vat,
+            self.items.iter().map(|item| item.total_price).sum(),
+        );
+        compare(
+            field_path(&totals_path, "total_vat"),
+            totals.total_vat,
+            self.vat_breakdown()
+                .iter()
+                .map(|summary| summary.vat_amount)
+                .sum(),
+        );
+        compare(
+            field_path(&totals_path, "total_including_vat"),
+            totals.total_including_vat,
+            totals.total_excluding_vat + totals.total_vat,
+        );
+
+        mismatches
+    }
+}
+
+impl VatRate {
+    /// Position of the rate in the VAT summary, rates with equal keys are ordered by percentage.
+    fn sort_key(&self) -> u8 {
+        match self {
+            Self::Percentage(_) => 0,
+            Self::Exempt => 1,
+            Self::ReverseCharge => 2,
+        }
+    }
+
+    /// The UNCL5305 code of the VAT category, used in e-invoices
+    pub(crate) fn category_code(&self) -> &'static str {
+        match self {
+            Self::Percentage(percentage) if percentage.is_zero() => "Z",
+            Self::Percentage(_) => "S",
+            Self::Exempt => "E",
+            Self::ReverseCharge => "AE",
+        }
+    }
+
+    /// The percentage of the price charged as VAT
+    pub(crate) fn percentage(&self) -> Decimal {
+        match self {
+            Self::Percentage(percentage) => *percentage,
+            Self::Exempt | Self::ReverseCharge => Decimal::ZERO,
+        }
+    }
+
+    /// Why no VAT is charged, as required in e-invoices for exempt and reverse-charged items
+    pub(crate) fn exemption_reason(&self) -> Option<&'static str> {
+        match self {
+            Self::Percentage(_) => None,
+            Self::Exempt => Some("Exempt from VAT"),
+            Self::ReverseCharge => Some("Reverse charge"),
+        }
+    }
+}
+
+impl Validate for InvoiceItem {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        require_non_empty(&self.identifier, path, "identifier", issues);
+        require_non_empty(&self.description, path, "description", issues);
+
+        if let VatRate::Percentage(percentage) = self.vat_rate {
+            if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&percentage) {
+                issues.push(ValidationIssue {
+                    path: field_path(path, "vat_rate"),
+                    message: format!("{percentage} is not between 0 and 100"),
+                });
+            }
+        }
+
+        if !(Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(&self.discount_percentage) {
+            issues.push(ValidationIssue {
+                path: field_path(path, "discount_percentage"),
+                message: format!("{} is not between 0 and 100", self.discount_percentage),
+            });
+        }
+    }
+}
+
+impl Validate for InvoiceTotals {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        let expected = self.total_excluding_vat + self.total_vat;
+        if (expected - self.total_including_vat).abs() > TOTALS_TOLERANCE {
+            issues.push(ValidationIssue {
+                path: field_path(path, "total_including_vat"),
+                message: format!(
+                    "{} does not equal the total excluding VAT plus VAT ({})",
+                    self.total_including_vat, expected
+                ),
+            });
+        }
+    }
+}
+
+/// The items of an invoice, spread over pages with their subtotal carried forward
+struct ItemsTable<'r, 'a> {
+    invoice: &'r Invoice,
+    target: &'r RenderTarget<'a>,
+}
+
+impl ItemsTable<'_, '_> {
+    /// The total price of the items in `range`
+    fn subtotal(&self, range: Range<usize>) -> Decimal {
+        self.invoice.items[range]
+            .iter()
+            .map(|item| item.total_price)
+            .sum()
+    }
+}
+
+impl<'a> PaginatedTable<'a> for ItemsTable<'_, 'a> {
+    fn columns(&self) -> Vec<f32> {
+        if self.invoice.any_item_has_discount() {
+            [1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0].to_vec()
+        } else {
+            [1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0].to_vec()
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        self.invoice.items.len()
+    }
+
+    fn render_header(&self, table: &Table<'a>, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        self.invoice.render_items_header(table, self.target, env)
+    }
+
+    fn render_row(&self, table: &Table<'a>, idx: usize, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        table.start_new_row(env)?;
+        let locale = self.target.translations.locale;
+        self.invoice
+            .render_item(table, &self.invoice.items[idx], locale, env)
+    }
+
+    fn render_brought_forward(
+        &self,
+        table: &Table<'a>,
+        printed: Range<usize>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        table.start_new_row(env)?;
+        let subtotal = self.subtotal(printed);
+        self.invoice
+            .render_carry_row(table, Label::BroughtForward, subtotal, self.target, env)
+    }
+
+    fn render_carried_forward(
+        &self,
+        table: &Table<'a>,
+        printed: Range<usize>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        table.start_new_row(env)?;
+        let subtotal = self.subtotal(printed);
+        self.invoice
+            .render_carry_row(table, Label::CarriedForward, subtotal, self.target, env)?;
+        let columns = self.columns().len();
+        self.invoice
+            .render_continued_row(table, columns, self.target, env)
+    }
 }
 
-const CONCERNING_LABEL: &str = "Betreft";
-const OUR_REFERENCE_LABEL: &str = "Referentie";
-const ORDER_ID_PREFIX: &str = "Bestelling #:";
-const INVOICE_DATE_LABEL: &str = "Factuurdatum";
-const EXPIRY_DATE_LABEL: &str = "Vervaldatum";
-const INVOICE_ID_LABEL: &str = "Factuurnummer";
-
-const ARTICLE_ID_LABEL: &str = "Artikelnummer";
-const DESCRIPTION_LABEL: &str = "Omschrijving";
-const QUANTITY_LABEL: &str = "Aantal";
-const PRICE_LABEL: &str = "Prijs";
-const DISCOUNT_LABEL: &str = "Korting";
-const SUBTOTAL_ITEM_PRICE_LABEL: &str = "Bedrag";
-const TOTAL_ITEM_LABEL: &str = "Totaal";
-
-const TOTAL_EXCLUDING_VAT_LABEL: &str = "Totaal excl. BTW";
-const TOTAL_VAT_LABEL: &str = "BTW";
-const TOTAL_PRICE_LABEL: &str = "Totaal te voldoen";
+/// The width and height of the EPC QR code, in points
+const PAYMENT_QR_CODE_SIZE: f32 = 80.0;
+
+/// The font size of the caption below the EPC QR code
+const PAYMENT_QR_CODE_CAPTION_SIZE: f32 = 8.0;
+
+/// UNTDID 1001 document type code of commercial invoices, used in e-invoices
+pub(crate) const COMMERCIAL_INVOICE: &str = "380";
+
+/// UN/ECE Recommendation 20 unit code of items counted in units ('one'), used in e-invoices
+pub(crate) const UNIT_CODE: &str = "C62";
+
+/// UNTDID 4461 payment means code of SEPA credit transfers, used in e-invoices
+pub(crate) const SEPA_CREDIT_TRANSFER: &str = "58";
+
+/// The amount of decimals percentages are printed with
+const PERCENTAGE_DECIMALS: u32 = 2;
+
+/// The amount of decimals item quantities are printed with
+const QUANTITY_DECIMALS: u32 = 2;
 
 impl Invoice {
     /// Render information about the invoice.
@@ -125,9 +685,11 @@ impl Invoice {
     /// If a JNI error occurs
     fn render_invoice_information<'a>(
         &self,
-        document: &Document<'a>,
+        target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
+        let document = &target.document;
+        let translations = &target.translations;
         let border = Border::Solid {
             color: ColorConstant::Black,
             width: 1.0,
@@ -139,7 +701,8 @@ impl Invoice {
             .start_new_row(env)?
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    Paragraph::new_with_text(CONCERNING_LABEL, env)?.set_bold(env)?,
+                    Paragraph::new_with_text(translations.get(Label::Concerning), env)?
+                        .set_bold_face(target.faces, env)?,
                     env,
                 )?,
                 env,
@@ -147,7 +710,11 @@ impl Invoice {
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
                     &Paragraph::new_with_text(
-                        &format!("{} {}", ORDER_ID_PREFIX, self.order_id),
+                        &format!(
+                            "{} {}",
+                            translations.get(Label::OrderIdPrefix),
+                            self.order_id
+                        ),
                         env,
                     )?,
                     env,
@@ -163,7 +730,8 @@ impl Invoice {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(border.clone(), env)?
                     .add(
-                        &Paragraph::new_with_text(OUR_REFERENCE_LABEL, env)?.set_bold(env)?,
+                        &Paragraph::new_with_text(translations.get(Label::Reference), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -179,7 +747,8 @@ impl Invoice {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(INVOICE_DATE_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::InvoiceDate), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -189,7 +758,8 @@ impl Invoice {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(EXPIRY_DATE_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::ExpirationDate), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -199,7 +769,8 @@ impl Invoice {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(INVOICE_ID_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::InvoiceId), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -222,14 +793,29 @@ impl Invoice {
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(border.clone(), env)?
-                    .add(&Paragraph::new_with_text(&self.invoice_date, env)?, env)?,
+                    .add(
+                        &Paragraph::new_with_text(
+                            &translations.locale.format_date(self.invoice_date),
+                            env,
+                        )?,
+                        env,
+                    )?,
                 env,
             )?
             .add_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(border.clone(), env)?
-                    .add(&Paragraph::new_with_text(&self.expiration_date, env)?, env)?,
+                    .add(
+                        &Paragraph::new_with_text(
+                            &self
+                                .expiration_date()
+                                .map(|date| translations.locale.format_date(date))
+                                .unwrap_or_default(),
+                            env,
+                        )?,
+                        env,
+                    )?,
                 env,
             )?
             .add_cell(
@@ -245,34 +831,99 @@ impl Invoice {
         Ok(())
     }
 
-    /// Render the items section of the invoice
+    /// Render the items section of the invoice.
+    /// Pages the items continue on are closed and opened with the subtotal of the items before the break.
     ///
     /// # Errors
     ///
     /// If a JNI error occurs
     fn render_items_section<'a>(
         &self,
-        document: &Document<'a>,
+        target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
-        let columns = if self.any_item_has_discount() {
-            [1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0].to_vec()
-        } else {
-            [1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0].to_vec()
+        let items = ItemsTable {
+            invoice: self,
+            target,
         };
+        add_paginated_table(&items, &target.document, env)
+    }
 
-        let table = Table::new(&columns, env)?;
-        table.use_all_available_width(env)?;
+    /// Render the subtotal of the items on the previous pages, or of the items up to and including this page.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_carry_row<'a>(
+        &self,
+        table: &Table<'a>,
+        label: Label,
+        subtotal: Decimal,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let translations = &target.translations;
+        let label_span = if self.any_item_has_discount() { 8 } else { 5 };
 
-        table.start_new_row(env)?;
-        self.render_items_header(&table, env)?;
+        table
+            .add_cell(
+                Cell::new_with_span(1, label_span, env)?
+                    .set_border(Border::NoBorder, env)?
+                    .add(
+                        Paragraph::new_with_text(translations.get(label), env)?
+                            .set_bold_face(target.faces, env)?,
+                        env,
+                    )?,
+                env,
+            )?
+            .add_cell(
+                Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
+                    &Paragraph::new_with_text(&self.currency_prefix(), env)?,
+                    env,
+                )?,
+                env,
+            )?
+            .add_cell(
+                Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
+                    Paragraph::new_with_text(
+                        &self.format_amount(subtotal, translations.locale),
+                        env,
+                    )?
+                    .set_bold_face(target.faces, env)?
+                    .set_text_alignment(TextAlignment::Right, env)?,
+                    env,
+                )?,
+                env,
+            )?;
 
-        for item in &self.items {
-            table.start_new_row(env)?;
-            self.render_item(&table, &item, env)?;
-        }
+        Ok(())
+    }
+
+    /// Render the notice that the items continue on the next page.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_continued_row<'a>(
+        &self,
+        table: &Table<'a>,
+        columns: usize,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let translations = &target.translations;
+        table.start_new_row(env)?.add_cell(
+            Cell::new_with_span(1, columns as i32, env)?
+                .set_border(Border::NoBorder, env)?
+                .add(
+                    Paragraph::new_with_text(translations.get(Label::ContinuedOnNextPage), env)?
+                        .set_italic_face(target.faces, env)?
+                        .set_text_alignment(TextAlignment::Right, env)?,
+                    env,
+                )?,
+            env,
+        )?;
 
-        document.add(table, env)?;
         Ok(())
     }
 
@@ -280,10 +931,40 @@ impl Invoice {
     fn any_item_has_discount(&self) -> bool {
         self.items
             .iter()
-            .find(|item| item.discount_percentage > 0.00)
+            .find(|item| item.discount_percentage > Decimal::ZERO)
             .is_some()
     }
 
+    /// Round an amount to the currency's minor units using the invoice's [Rounding]
+    /// and format it according to the locale.
+    /// The currency symbol is included if it is placed after the amount.
+    fn format_amount(&self, amount: Decimal, locale: Locale) -> String {
+        let decimals = self.currency.minor_units();
+        let amount = locale.format_decimal(self.rounding.round(amount, decimals), decimals);
+        match self.currency.placement() {
+            SymbolPlacement::Prefix => amount,
+            SymbolPlacement::Suffix => format!("{amount} {}", self.currency.symbol()),
+        }
+    }
+
+    /// Format a percentage according to the locale
+    fn format_percentage(&self, percentage: Decimal, locale: Locale) -> String {
+        let percentage = self.rounding.round(percentage, PERCENTAGE_DECIMALS);
+        format!(
+            "{}%",
+            locale.format_decimal(percentage, PERCENTAGE_DECIMALS)
+        )
+    }
+
+    /// The text printed in the column before an amount.
+    /// This is the currency symbol if it is placed before the amount, otherwise empty.
+    fn currency_prefix(&self) -> String {
+        match self.currency.placement() {
+            SymbolPlacement::Prefix => self.currency.symbol().to_string(),
+            SymbolPlacement::Suffix => String::new(),
+        }
+    }
+
     /// Render the items section table header.
     ///
     /// # Errors
@@ -292,43 +973,47 @@ impl Invoice {
     fn render_items_header<'a>(
         &self,
         table: &Table<'a>,
+        target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
+        let translations = &target.translations;
         let column_headers = if self.any_item_has_discount() {
             vec![
-                Some(ARTICLE_ID_LABEL),
-                Some(DESCRIPTION_LABEL),
-                Some(QUANTITY_LABEL),
+                Some(Label::ArticleId),
+                Some(Label::Description),
+                Some(Label::Quantity),
                 None,
-                Some(PRICE_LABEL),
-                Some(DISCOUNT_LABEL),
+                Some(Label::Price),
+                Some(Label::Discount),
                 None,
-                Some(SUBTOTAL_ITEM_PRICE_LABEL),
+                Some(Label::ItemSubtotal),
                 None,
-                Some(TOTAL_ITEM_LABEL),
+                Some(Label::ItemTotal),
             ]
         } else {
             vec![
-                Some(ARTICLE_ID_LABEL),
-                Some(DESCRIPTION_LABEL),
-                Some(QUANTITY_LABEL),
+                Some(Label::ArticleId),
+                Some(Label::Description),
+                Some(Label::Quantity),
                 None,
-                Some(PRICE_LABEL),
+                Some(Label::Price),
                 None,
-                Some(TOTAL_ITEM_LABEL),
+                Some(Label::ItemTotal),
             ]
         };
 
         for header in column_headers {
             if let Some(label) = header {
-                table.add_cell(
-                    Cell::new(env)?
-                        .set_border(Border::NoBorder, env)?
-                        .add(Paragraph::new_with_text(label, env)?.set_bold(env)?, env)?,
+                table.add_header_cell(
+                    Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
+                        Paragraph::new_with_text(translations.get(label), env)?
+                            .set_bold_face(target.faces, env)?,
+                        env,
+                    )?,
                     env,
                 )?;
             } else {
-                table.add_cell(Cell::new(env)?.set_border(Border::NoBorder, env)?, env)?;
+                table.add_header_cell(Cell::new(env)?.set_border(Border::NoBorder, env)?, env)?;
             }
         }
 
@@ -344,22 +1029,30 @@ impl Invoice {
         &self,
         table: &Table<'a>,
         item: &InvoiceItem,
+        locale: Locale,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
         let values = vec![
             Some((item.identifier.clone(), false)),
             Some((item.description.clone(), false)),
-            Some((format!("{:.2}", item.quantity as f32), false)),
-            Some((self.currency.to_string(), false)),
-            Some((format!("{:.2}", item.price_per_unit), true)),
-            self.any_item_has_discount()
-                .then_some((format!("{:.2}%", item.discount_percentage), true)),
-            self.any_item_has_discount()
-                .then_some((self.currency.to_string(), true)),
+            Some((
+                locale.format_decimal(Decimal::from(item.quantity), QUANTITY_DECIMALS),
+                false,
+            )),
+            Some((self.currency_prefix(), false)),
+            Some((self.format_amount(item.price_per_unit, locale), true)),
+            self.any_item_has_discount().then_some((
+                self.format_percentage(item.discount_percentage, locale),
+                true,
+            )),
             self.any_item_has_discount()
-                .then_some((format!("{:.2}", item.subtotal_price_per_unit), true)),
-            Some((self.currency.to_string(), false)),
-            Some((format!("{:.2}", item.total_price), true)),
+                .then_some((self.currency_prefix(), true)),
+            self.any_item_has_discount().then_some((
+                self.format_amount(item.subtotal_price_per_unit, locale),
+                true,
+            )),
+            Some((self.currency_prefix(), false)),
+            Some((self.format_amount(item.total_price, locale), true)),
         ];
 
         for value in values {
@@ -373,6 +1066,7 @@ impl Invoice {
                 table.add_cell(
                     Cell::new(env)?
                         .set_border(Border::NoBorder, env)?
+                        .set_keep_together(true, env)?
                         .add(&paragraph, env)?,
                     env,
                 )?;
@@ -382,7 +1076,9 @@ impl Invoice {
         Ok(())
     }
 
-    /// Render the invoice totals at the bottom of the last page.
+    /// Render the invoice totals and the VAT summary after the items,
+    /// or at the bottom of the last page, depending on the [TotalsPlacement].
+    /// They are kept together and moved to the next page if they do not fit.
     ///
     /// # Errors
     ///
@@ -392,44 +1088,86 @@ impl Invoice {
         render_target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
+        let doc = &render_target.document;
+
+        let left = Cell::new(env)?;
+        left.set_border(Border::NoBorder, env)?
+            .add(&self.new_vat_summary_table(render_target, env)?, env)?;
+        if self.payment_qr_code {
+            let qr_code =
+                self.epc_qr_code()?
+                    .to_image(PAYMENT_QR_CODE_SIZE, PAYMENT_QR_CODE_SIZE, env)?;
+            let caption =
+                Paragraph::new_with_text(render_target.translations.get(Label::ScanToPay), env)?;
+            caption
+                .set_font_size(PAYMENT_QR_CODE_CAPTION_SIZE, env)?
+                .set_margin_top(0.0, env)?;
+            left.add_image(&qr_code, env)?.add(&caption, env)?;
+        }
+
+        let block = Table::new(&[1.0, 1.0], env)?;
+        block
+            .use_all_available_width(env)?
+            .set_keep_together(true, env)?
+            .add_cell(&left, env)?
+            .add_cell(
+                Cell::new(env)?
+                    .set_border(Border::NoBorder, env)?
+                    .add(&self.new_totals_table(render_target, env)?, env)?,
+                env,
+            )?;
+
+        if self.totals_placement == TotalsPlacement::BottomOfLastPage {
+            let width = doc
+                .get_pdf_document(env)?
+                .get_default_page_size(env)?
+                .get_width(env)?
+                - doc.get_left_margin(env)?
+                - doc.get_right_margin(env)?;
+            let height = doc.measure_height(&block, width, env)?;
+
+            if doc.get_remaining_height(env)? < height {
+                doc.add_area_break(env)?;
+            }
+
+            let remaining = doc.get_remaining_height(env)?;
+            block.set_margin_top((remaining - height).max(0.0), env)?;
+        }
+
+        doc.add(block, env)?;
+        Ok(())
+    }
+
+    /// Create the table with the invoice totals.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn new_totals_table<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<Table<'a>, Error> {
+        let translations = &target.translations;
         let border = Border::Solid {
             color: ColorConstant::Black,
             width: 1.0,
         };
 
         let table = Table::new(&[1.0, 1.0, 1.0], env)?;
-
-        let page_width = render_target
-            .document
-            .get_pdf_document(env)?
-            .get_default_page_size(env)?
-            .get_width(env)?;
-
-        const BOTTOM_OFFSET: f32 = 100.0;
-
         table
-            .set_fixed_position(
-                render_target.document.get_left_margin(env)?
-                    + render_target.document.get_right_margin(env)?
-                    + page_width / 2.0,
-                render_target.document.get_bottom_margin(env)? + BOTTOM_OFFSET,
-                page_width
-                    - render_target.document.get_left_margin(env)?
-                    - render_target.document.get_right_margin(env)?,
-                env,
-            )?
             .use_all_available_width(env)?
             .start_new_row(env)?
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(TOTAL_EXCLUDING_VAT_LABEL, env)?,
+                    &Paragraph::new_with_text(translations.get(Label::TotalExcludingVat), env)?,
                     env,
                 )?,
                 env,
             )?
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(&self.currency.to_string(), env)?,
+                    &Paragraph::new_with_text(&self.currency_prefix(), env)?,
                     env,
                 )?,
                 env,
@@ -437,7 +1175,7 @@ impl Invoice {
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
                     &Paragraph::new_with_text(
-                        &format!("{:.2}", self.totals.total_excluding_vat),
+                        &self.format_amount(self.totals.total_excluding_vat, translations.locale),
                         env,
                     )?
                     .set_text_alignment(TextAlignment::Right, env)?,
@@ -450,7 +1188,10 @@ impl Invoice {
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(border.clone(), env)?
-                    .add(&Paragraph::new_with_text(TOTAL_VAT_LABEL, env)?, env)?,
+                    .add(
+                        &Paragraph::new_with_text(translations.get(Label::TotalVat), env)?,
+                        env,
+                    )?,
                 env,
             )?
             .add_cell(
@@ -458,7 +1199,7 @@ impl Invoice {
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(border.clone(), env)?
                     .add(
-                        &Paragraph::new_with_text(&self.currency.to_string(), env)?,
+                        &Paragraph::new_with_text(&self.currency_prefix(), env)?,
                         env,
                     )?,
                 env,
@@ -468,8 +1209,11 @@ impl Invoice {
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(border.clone(), env)?
                     .add(
-                        &Paragraph::new_with_text(&format!("{:.2}", self.totals.total_vat), env)?
-                            .set_text_alignment(TextAlignment::Right, env)?,
+                        &Paragraph::new_with_text(
+                            &self.format_amount(self.totals.total_vat, translations.locale),
+                            env,
+                        )?
+                        .set_text_alignment(TextAlignment::Right, env)?,
                         env,
                     )?,
                 env,
@@ -477,14 +1221,16 @@ impl Invoice {
             .start_new_row(env)?
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(TOTAL_PRICE_LABEL, env)?.set_bold(env)?,
+                    &Paragraph::new_with_text(translations.get(Label::TotalPayable), env)?
+                        .set_bold_face(target.faces, env)?,
                     env,
                 )?,
                 env,
             )?
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(&self.currency.to_string(), env)?.set_bold(env)?,
+                    &Paragraph::new_with_text(&self.currency_prefix(), env)?
+                        .set_bold_face(target.faces, env)?,
                     env,
                 )?,
                 env,
@@ -492,26 +1238,512 @@ impl Invoice {
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
                     &Paragraph::new_with_text(
-                        &format!("{:.2}", self.totals.total_including_vat),
+                        &self.format_amount(self.totals.total_including_vat, translations.locale),
                         env,
                     )?
                     .set_text_alignment(TextAlignment::Right, env)?
-                    .set_bold(env)?,
+                    .set_bold_face(target.faces, env)?,
                     env,
                 )?,
                 env,
             )?;
 
-        render_target.document.add(table, env)?;
-        Ok(())
+        Ok(table)
+    }
+
+    /// Create the table with the base amount and VAT amount per VAT rate.
+    /// Placed on the left half of the page, next to the invoice totals.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn new_vat_summary_table<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<Table<'a>, Error> {
+        let translations = &target.translations;
+        let border = Border::Solid {
+            color: ColorConstant::Black,
+            width: 1.0,
+        };
+
+        let table = Table::new(&[1.0, 0.5, 1.0, 0.5, 1.0], env)?;
+        table
+            .use_all_available_width(env)?
+            .set_margin_right(VAT_SUMMARY_GAP, env)?
+            .start_new_row(env)?;
+
+        for header in [
+            Some(Label::VatRate),
+            None,
+            Some(Label::VatBase),
+            None,
+            Some(Label::VatAmount),
+        ] {
+            let cell = Cell::new(env)?;
+            cell.set_border(Border::NoBorder, env)?
+                .set_border_bottom(border.clone(), env)?;
+            if let Some(label) = header {
+                cell.add(
+                    Paragraph::new_with_text(translations.get(label), env)?
+                        .set_bold_face(target.faces, env)?,
+                    env,
+                )?;
+            }
+
+            table.add_cell(&cell, env)?;
+        }
+
+        for summary in self.vat_breakdown() {
+            let values = [
+                (vat_rate_label(&summary.rate, translations), false),
+                (self.currency_prefix(), false),
+                (
+                    self.format_amount(summary.base_amount, translations.locale),
+                    true,
+                ),
+                (self.currency_prefix(), false),
+                (
+                    self.format_amount(summary.vat_amount, translations.locale),
+                    true,
+                ),
+            ];
+
+            table.start_new_row(env)?;
+            for (value, right_aligned) in values {
+                let paragraph = Paragraph::new_with_text(&value, env)?;
+                if right_aligned {
+                    paragraph.set_text_alignment(TextAlignment::Right, env)?;
+                }
+
+                table.add_cell(
+                    Cell::new(env)?
+                        .set_border(Border::NoBorder, env)?
+                        .add(&paragraph, env)?,
+                    env,
+                )?;
+            }
+        }
+
+        Ok(table)
     }
 }
 
-impl ToString for Currency {
-    fn to_string(&self) -> String {
-        match self {
-            Self::Euro => "€",
+/// The label of a VAT rate in the VAT summary, e.g. `21%`
+fn vat_rate_label(rate: &VatRate, translations: &Translations) -> String {
+    match rate {
+        VatRate::Percentage(percentage) => {
+            let percentage = percentage.normalize();
+            format!(
+                "{}%",
+                translations
+                    .locale
+                    .format_decimal(percentage, percentage.scale())
+            )
+        }
+        VatRate::Exempt => translations.get(Label::VatExempt).to_string(),
+        VatRate::ReverseCharge => translations.get(Label::VatReverseCharge).to_string(),
+    }
+}
+
+/// The horizontal space between the VAT summary and the invoice totals
+const VAT_SUMMARY_GAP: f32 = 10.0;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::header::{
+        Address, AddressableParty, BankAccount, ElectronicAddress, LegalRegistration,
+    };
+    use rust_decimal_macros::dec;
+
+    /// A Dutch invoice with consistent totals, which is valid as an e-invoice
+    pub(crate) fn sample_invoice() -> Invoice {
+        Invoice {
+            header: Header {
+                label: "Factuur".to_string(),
+                logo: b"\x89PNG\r\n\x1a\n".to_vec(),
+                logo_on_every_page: false,
+                seller: AddressableParty {
+                    name: "Mr.Friendly B.V.".to_string(),
+                    department: None,
+                    address: Address {
+                        street: "De Boomgaard".to_string(),
+                        number: "11-07".to_string(),
+                        zipcode: "1243 HV".to_string(),
+                        city: "'s-Graveland".to_string(),
+                        country: "Nederland".to_string(),
+                        country_code: Some("NL".to_string()),
+                    },
+                    vat_number: Some("NL856883566B01".to_string()),
+                    electronic_address: Some(ElectronicAddress {
+                        scheme: "0106".to_string(),
+                        identifier: "67223370".to_string(),
+                    }),
+                    legal_registration: Some(LegalRegistration {
+                        scheme: "0106".to_string(),
+                        identifier: "67223370".to_string(),
+                    }),
+                    bank_account: Some(BankAccount {
+                        iban: "NL75 INGB 0007 5383 83".to_string(),
+                        bic: Some("INGBNL2A".to_string()),
+                    }),
+                },
+                addressed_to: AddressableParty {
+                    name: "Smith & Sons".to_string(),
+                    department: None,
+                    address: Address {
+                        street: "Struikheiweg".to_string(),
+                        number: "14".to_string(),
+                        zipcode: "1406 TK".to_string(),
+                        city: "Bussum".to_string(),
+                        country: "Nederland".to_string(),
+                        country_code: Some("NL".to_string()),
+                    },
+                    vat_number: None,
+                    electronic_address: None,
+                    legal_registration: None,
+                    bank_account: None,
+                },
+            },
+            footer: Footer {
+                fields: Vec::new(),
+                company_name: "Mr.Friendly B.V.".to_string(),
+                company_name_color: "#3BAF29".to_string(),
+            },
+            reference: "PO-7".to_string(),
+            order_id: "10315".to_string(),
+            invoice_id: "230307".to_string(),
+            invoice_date: NaiveDate::from_ymd_opt(2023, 6, 19).unwrap(),
+            payment_term: PaymentTerm::Net(30),
+            totals: InvoiceTotals {
+                total_excluding_vat: dec!(129.85),
+                total_vat: dec!(26.08),
+                total_including_vat: dec!(155.93),
+            },
+            items: vec![
+                InvoiceItem {
+                    identifier: "16005-3".to_string(),
+                    description: "Filter Globe".to_string(),
+                    quantity: 2,
+                    price_per_unit: dec!(59.95),
+                    discount_percentage: dec!(0),
+                    subtotal_price_per_unit: dec!(59.95),
+                    total_price: dec!(119.90),
+                    vat_rate: VatRate::Percentage(dec!(21)),
+                },
+                InvoiceItem {
+                    identifier: "B-1".to_string(),
+                    description: "Manual".to_string(),
+                    quantity: 1,
+                    price_per_unit: dec!(9.95),
+                    discount_percentage: dec!(0),
+                    subtotal_price_per_unit: dec!(9.95),
+                    total_price: dec!(9.95),
+                    vat_rate: VatRate::Percentage(dec!(9)),
+                },
+            ],
+            note: None,
+            currency: Currency::Euro,
+            rounding: Rounding::HalfUp,
+            strict_totals: None,
+            totals_placement: TotalsPlacement::Flow,
+            factur_x: false,
+            payment_qr_code: false,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn net_term_counts_days_from_invoice_date() {
+        let term = PaymentTerm::Net(30);
+        assert_eq!(term.due_date(date(2023, 6, 19)), Some(date(2023, 7, 19)));
+        assert_eq!(term.due_date(date(2023, 12, 15)), Some(date(2024, 1, 14)));
+        assert_eq!(
+            PaymentTerm::Net(0).due_date(date(2023, 6, 19)),
+            Some(date(2023, 6, 19))
+        );
+        assert_eq!(PaymentTerm::Net(1).due_date(NaiveDate::MAX), None);
+    }
+
+    #[test]
+    fn end_of_month_term_counts_days_from_last_day_of_month() {
+        let term = PaymentTerm::EndOfMonth(14);
+        assert_eq!(term.due_date(date(2023, 6, 19)), Some(date(2023, 7, 14)));
+        assert_eq!(term.due_date(date(2023, 1, 31)), Some(date(2023, 2, 14)));
+        assert_eq!(term.due_date(date(2023, 2, 1)), Some(date(2023, 3, 14)));
+        assert_eq!(term.due_date(date(2023, 2, 28)), Some(date(2023, 3, 14)));
+        assert_eq!(term.due_date(date(2023, 12, 1)), Some(date(2024, 1, 14)));
+        assert_eq!(term.due_date(date(2023, 12, 31)), Some(date(2024, 1, 14)));
+
+        let end_of_month = PaymentTerm::EndOfMonth(0);
+        assert_eq!(
+            end_of_month.due_date(date(2023, 1, 31)),
+            Some(date(2023, 1, 31))
+        );
+        assert_eq!(
+            end_of_month.due_date(date(2023, 2, 10)),
+            Some(date(2023, 2, 28))
+        );
+        // Leap year
+        assert_eq!(
+            end_of_month.due_date(date(2024, 2, 10)),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(
+            end_of_month.due_date(date(2023, 12, 10)),
+            Some(date(2023, 12, 31))
+        );
+    }
+
+    #[test]
+    fn fixed_term_ignores_invoice_date() {
+        let term = PaymentTerm::On(date(2023, 8, 1));
+        assert_eq!(term.due_date(date(2023, 6, 19)), Some(date(2023, 8, 1)));
+        assert_eq!(term.due_date(date(2024, 1, 1)), Some(date(2023, 8, 1)));
+    }
+
+    fn item(total_price: Decimal, vat_rate: VatRate) -> InvoiceItem {
+        InvoiceItem {
+            identifier: "A-1".to_string(),
+            description: "Article".to_string(),
+            quantity: 1,
+            price_per_unit: total_price,
+            discount_percentage: dec!(0),
+            subtotal_price_per_unit: total_price,
+            total_price,
+            vat_rate,
         }
-        .to_string()
+    }
+
+    #[test]
+    fn groups_vat_by_rate() {
+        let mut invoice = sample_invoice();
+        invoice.items = vec![
+            item(dec!(10.00), VatRate::ReverseCharge),
+            item(dec!(10.00), VatRate::Percentage(dec!(9))),
+            item(dec!(20.00), VatRate::Exempt),
+            item(dec!(0.07), VatRate::Percentage(dec!(21))),
+            item(dec!(5.00), VatRate::Percentage(dec!(0))),
+            item(dec!(0.07), VatRate::Percentage(dec!(21.0))),
+            item(dec!(30.00), VatRate::Exempt),
+        ];
+
+        let summary = |rate, base_amount, vat_amount| VatSummary {
+            rate,
+            base_amount,
+            vat_amount,
+        };
+        assert_eq!(
+            invoice.vat_breakdown(),
+            [
+                // VAT is computed over the base amount per rate: 21% of 0.14, not twice 21% of 0.07
+                summary(VatRate::Percentage(dec!(21)), dec!(0.14), dec!(0.03)),
+                summary(VatRate::Percentage(dec!(9)), dec!(10.00), dec!(0.90)),
+                summary(VatRate::Percentage(dec!(0)), dec!(5.00), dec!(0)),
+                summary(VatRate::Exempt, dec!(50.00), dec!(0)),
+                summary(VatRate::ReverseCharge, dec!(10.00), dec!(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn vat_breakdown_uses_rounding() {
+        let mut invoice = sample_invoice();
+        // 21% of 0.50 is 0.105
+        invoice.items = vec![item(dec!(0.50), VatRate::Percentage(dec!(21)))];
+        assert_eq!(invoice.vat_breakdown()[0].vat_amount, dec!(0.11));
+
+        invoice.rounding = Rounding::HalfEven;
+        assert_eq!(invoice.vat_breakdown()[0].vat_amount, dec!(0.10));
+    }
+
+    #[test]
+    fn maps_vat_rates_to_categories() {
+        let categories = [
+            (VatRate::Percentage(dec!(21)), "S", dec!(21), None),
+            (VatRate::Percentage(dec!(0)), "Z", dec!(0), None),
+            (VatRate::Exempt, "E", dec!(0), Some("Exempt from VAT")),
+            (
+                VatRate::ReverseCharge,
+                "AE",
+                dec!(0),
+                Some("Reverse charge"),
+            ),
+        ];
+        for (rate, code, percentage, reason) in categories {
+            assert_eq!(rate.category_code(), code, "{rate:?}");
+            assert_eq!(rate.percentage(), percentage, "{rate:?}");
+            assert_eq!(rate.exemption_reason(), reason, "{rate:?}");
+        }
+    }
+
+    fn strict(tolerance: Decimal) -> StrictTotals {
+        StrictTotals { tolerance }
+    }
+
+    #[test]
+    fn verifies_consistent_totals() {
+        assert_eq!(sample_invoice().verify_totals(&strict(dec!(0))), Vec::new());
+    }
+
+    #[test]
+    fn allows_differences_up_to_tolerance() {
+        let mut invoice = sample_invoice();
+        invoice.totals.total_vat = dec!(26.09);
+        invoice.totals.total_including_vat = dec!(155.94);
+        assert_eq!(invoice.verify_totals(&strict(dec!(0.01))), Vec::new());
+
+        invoice.totals.total_vat = dec!(26.10);
+        invoice.totals.total_including_vat = dec!(155.95);
+        assert_eq!(
+            invoice.verify_totals(&strict(dec!(0.01))),
+            [ValidationIssue {
+                path: "totals.total_vat".to_string(),
+                message: "expected 26.08, got 26.10".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn verifies_item_amounts() {
+        let mut invoice = sample_invoice();
+        // 10% off 59.95 is 53.955, rounded half up
+        invoice.items[0].discount_percentage = dec!(10);
+        invoice.items[0].subtotal_price_per_unit = dec!(53.95);
+        invoice.items[1].total_price = dec!(9.90);
+
+        let issues = invoice.verify_totals(&strict(dec!(0)));
+        let messages: Vec<_> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "items[0].subtotal_price_per_unit: expected 53.96, got 53.95",
+                "items[0].total_price: expected 107.90, got 119.90",
+                "items[1].total_price: expected 9.95, got 9.90",
+                "totals.total_excluding_vat: expected 129.80, got 129.85",
+                "totals.total_vat: expected 26.07, got 26.08",
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_totals_are_verified_during_validation() {
+        let mut invoice = sample_invoice();
+        invoice.totals.total_excluding_vat = dec!(129.95);
+        invoice.totals.total_including_vat = dec!(156.03);
+        assert_eq!(invoice.validate(), Vec::new());
+
+        invoice.strict_totals = Some(strict(dec!(0.05)));
+        let paths: Vec<_> = invoice
+            .validate()
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        assert_eq!(paths, ["totals.total_excluding_vat"]);
+    }
+
+    fn e_invoice_issues(invoice: &Invoice) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        invoice.validate_e_invoice_at("", &mut issues);
+        issues
+    }
+
+    #[test]
+    fn sample_invoice_is_valid() {
+        assert_eq!(sample_invoice().validate(), Vec::new());
+    }
+
+    #[test]
+    fn reports_paths_of_invalid_fields() {
+        let mut invoice = sample_invoice();
+        invoice.header.seller.address.city = " ".to_string();
+        invoice.header.addressed_to.address.country_code = Some("nl".to_string());
+        invoice.footer.company_name_color = "green".to_string();
+        invoice.order_id = String::new();
+        invoice.items[1].description = String::new();
+        invoice.items[1].vat_rate = VatRate::Percentage(dec!(121));
+        invoice.totals.total_including_vat = dec!(100);
+
+        let paths: Vec<_> = invoice
+            .validate()
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "header.addressed_to.address.country_code",
+                "header.seller.address.city",
+                "footer.company_name_color",
+                "order_id",
+                "items[1].description",
+                "items[1].vat_rate",
+                "totals.total_including_vat",
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_items() {
+        let mut invoice = sample_invoice();
+        invoice.items.clear();
+        invoice.totals = InvoiceTotals {
+            total_excluding_vat: dec!(0),
+            total_vat: dec!(0),
+            total_including_vat: dec!(0),
+        };
+        let issues = invoice.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].to_string(),
+            "items: must contain at least one item"
+        );
+    }
+
+    #[test]
+    fn sample_invoice_is_a_valid_e_invoice() {
+        assert_eq!(e_invoice_issues(&sample_invoice()), Vec::new());
+    }
+
+    #[test]
+    fn e_invoice_totals_must_add_up_exactly() {
+        let mut invoice = sample_invoice();
+        invoice.totals.total_vat = dec!(26.09);
+        invoice.totals.total_including_vat = dec!(155.94);
+
+        let issues = e_invoice_issues(&invoice);
+        let paths: Vec<_> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, ["totals.total_vat", "totals.total_including_vat"]);
+        assert_eq!(issues[0].message, "must be 26.08 for e-invoices, got 26.09");
+    }
+
+    #[test]
+    fn e_invoice_line_amounts_must_equal_quantity_times_price() {
+        let mut invoice = sample_invoice();
+        invoice.items[0].total_price = dec!(119.95);
+        invoice.totals.total_excluding_vat = dec!(129.90);
+        invoice.totals.total_vat = dec!(26.09);
+        invoice.totals.total_including_vat = dec!(155.99);
+
+        let issues = e_invoice_issues(&invoice);
+        let paths: Vec<_> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, ["items[0].total_price"]);
+        assert_eq!(
+            issues[0].message,
+            "must be 119.90 for e-invoices, got 119.95"
+        );
+    }
+
+    #[test]
+    fn e_invoice_line_total_sums_rounded_lines() {
+        let mut invoice = sample_invoice();
+        invoice.items[0].total_price = dec!(119.904);
+        invoice.items[1].total_price = dec!(9.954);
+        assert_eq!(invoice.e_invoice_line_total(), dec!(129.85));
     }
 }
@@ -2,16 +2,124 @@ use crate::error::Error;
 use jni::{AttachGuard, InitArgsBuilder, JNIVersion, JavaVM};
 use std::fs;
 use std::io::Write;
-use tempfile::TempDir;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use tempfile::{NamedTempFile, TempDir};
+
+/// The process-wide JVM, see [JVM::global]
+static GLOBAL_JVM: OnceLock<JVM> = OnceLock::new();
+/// Guards initialization of [GLOBAL_JVM]
+static GLOBAL_JVM_INIT: Mutex<()> = Mutex::new(());
+
+/// Options used to launch the JVM.
+///
+/// ```no_run
+/// # use order_pdf_printer::{JvmConfig, JVM};
+/// let config = JvmConfig::new()
+///     .max_heap("512m")
+///     .system_property("java.awt.headless", "true")
+///     .jar_cache_dir("/var/cache/order-pdf-printer");
+/// let jvm = JVM::with_config(&config).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct JvmConfig {
+    /// The JNI version to request
+    version: JNIVersion,
+    /// Initial heap size, passed as `-Xms`
+    initial_heap: Option<String>,
+    /// Maximum heap size, passed as `-Xmx`
+    max_heap: Option<String>,
+    /// Jars to add to the classpath next to the bundled dependencies
+    classpath: Vec<PathBuf>,
+    /// System properties, passed as `-Dkey=value`
+    system_properties: Vec<(String, String)>,
+    /// Any other options passed verbatim
+    options: Vec<String>,
+    /// Directory in which the bundled jars are cached between launches.
+    /// If None, the jars are extracted to a temporary directory.
+    jar_cache_dir: Option<PathBuf>,
+}
+
+impl Default for JvmConfig {
+    fn default() -> Self {
+        Self {
+            version: JNIVersion::V8,
+            initial_heap: None,
+            max_heap: None,
+            classpath: Vec::new(),
+            system_properties: Vec::new(),
+            options: Vec::new(),
+            jar_cache_dir: None,
+        }
+    }
+}
+
+impl JvmConfig {
+    /// Create a new configuration with the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The JNI version to request. Defaults to [JNIVersion::V8].
+    pub fn version(mut self, version: JNIVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// The initial heap size, e.g. `256m`
+    pub fn initial_heap(mut self, size: impl Into<String>) -> Self {
+        self.initial_heap = Some(size.into());
+        self
+    }
+
+    /// The maximum heap size, e.g. `1g`
+    pub fn max_heap(mut self, size: impl Into<String>) -> Self {
+        self.max_heap = Some(size.into());
+        self
+    }
+
+    /// Add a jar to the classpath, e.g. an iText add-on such as `pdfa-7.1.18.jar`,
+    /// which [crate::DocumentConfiguration::pdf_a] requires
+    pub fn classpath_jar(mut self, jar: impl Into<PathBuf>) -> Self {
+        self.classpath.push(jar.into());
+        self
+    }
+
+    /// Set a system property
+    pub fn system_property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.system_properties.push((key.into(), value.into()));
+        self
+    }
+
+    /// Pass an option verbatim to the JVM, e.g. `-XX:+UseSerialGC`
+    pub fn option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Cache the bundled jars in `dir` instead of extracting them
+    /// to a new temporary directory on every launch.
+    /// The directory is created if it does not exist.
+    /// Jars extracted by other versions of this crate are removed from it.
+    pub fn jar_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.jar_cache_dir = Some(dir.into());
+        self
+    }
+}
 
 /// Java Virtual Machine
 pub struct JVM {
     jvm: JavaVM,
-    _dep_dir: TempDir,
+    /// Temporary directory holding the bundled jars.
+    /// None if the jars live in a persistent cache directory.
+    _dep_dir: Option<TempDir>,
 }
 
 impl JVM {
-    /// Create a new JVM instance
+    /// Create a new JVM instance with the default configuration.
+    ///
+    /// Only one JVM can exist per process, prefer [JVM::global]
+    /// if the JVM is shared between multiple users.
     ///
     /// # Errors
     ///
@@ -19,42 +127,153 @@ impl JVM {
     /// - If a JNI error occurs
     /// - If the JVM cannot be launched
     pub fn new() -> Result<Self, Error> {
-        let dependency_dir = TempDir::new()?;
+        Self::with_config(&JvmConfig::default())
+    }
 
-        // Save logger adapter
-        let logger_jar = dependency_dir.path().join("logger.jar");
-        let mut file = fs::File::create(&logger_jar)?;
-        file.write_all(tracing_slf4j::DEPENDENCIES)?;
+    /// Create a new JVM instance with the provided configuration.
+    ///
+    /// Only one JVM can exist per process, prefer [JVM::init_global]
+    /// if the JVM is shared between multiple users.
+    ///
+    /// # Errors
+    ///
+    /// - If an IO error occurs
+    /// - If a JNI error occurs
+    /// - If the JVM cannot be launched
+    pub fn with_config(config: &JvmConfig) -> Result<Self, Error> {
+        let (dependency_dir, temp_dir) = match &config.jar_cache_dir {
+            Some(dir) => {
+                fs::create_dir_all(dir)?;
+                (dir.clone(), None)
+            }
+            None => {
+                let temp_dir = TempDir::new()?;
+                (temp_dir.path().to_path_buf(), Some(temp_dir))
+            }
+        };
 
+        // Save logger adapter
+        let logger_jar = Self::extract_jar(&dependency_dir, "logger", tracing_slf4j::DEPENDENCIES)?;
         // Save itext
-        let itext_jar = dependency_dir.path().join("itext.jar");
-        let mut file = fs::File::create(&itext_jar)?;
-        file.write_all(itext::bundle::DEPENDENCIES)?;
+        let itext_jar = Self::extract_jar(&dependency_dir, "itext", itext::bundle::DEPENDENCIES)?;
 
-        let classpath = vec![
-            logger_jar.to_string_lossy().to_string(),
-            itext_jar.to_string_lossy().to_string(),
-        ]
-        .join(":");
+        let classpath = [logger_jar, itext_jar]
+            .iter()
+            .chain(&config.classpath)
+            .map(|jar| jar.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(":");
 
         // Configure JVM
-        let init_args = InitArgsBuilder::new()
-            .version(JNIVersion::V8)
-            .option(format!("-Djava.class.path={classpath}"))
-            .build()?;
+        let mut init_args = InitArgsBuilder::new()
+            .version(config.version)
+            .option(format!("-Djava.class.path={classpath}"));
+
+        if let Some(size) = &config.initial_heap {
+            init_args = init_args.option(format!("-Xms{size}"));
+        }
+
+        if let Some(size) = &config.max_heap {
+            init_args = init_args.option(format!("-Xmx{size}"));
+        }
 
-        let jvm = JavaVM::new(init_args)?;
+        for (key, value) in &config.system_properties {
+            init_args = init_args.option(format!("-D{key}={value}"));
+        }
+
+        for option in &config.options {
+            init_args = init_args.option(option.as_str());
+        }
+
+        let jvm = JavaVM::new(init_args.build()?)?;
 
         let mut env = jvm.attach_current_thread()?;
         tracing_slf4j::register_log_fn(&mut env)?;
         drop(env);
 
         Ok(Self {
-            _dep_dir: dependency_dir,
+            _dep_dir: temp_dir,
             jvm,
         })
     }
 
+    /// Write a jar to `dir`, unless an identical jar is already present.
+    /// The file name contains a digest of the contents, so an outdated
+    /// jar in a cache directory is never picked up. Jars of other versions
+    /// with the same name are removed from `dir`.
+    ///
+    /// # Errors
+    ///
+    /// If an IO error occurs
+    fn extract_jar(dir: &Path, name: &str, contents: &[u8]) -> Result<PathBuf, Error> {
+        let digest = jar_digest(contents);
+        let file_name = format!("{name}-{digest:016x}.jar");
+        let path = dir.join(&file_name);
+
+        // A jar which was corrupted or only partially written by an older version is replaced
+        let up_to_date = fs::read(&path)
+            .map(|existing| jar_digest(&existing) == digest)
+            .unwrap_or(false);
+        if !up_to_date {
+            // Write to a temporary file first, so a concurrently starting
+            // process never observes a partially written jar.
+            let mut file = NamedTempFile::new_in(dir)?;
+            file.write_all(contents)?;
+            file.persist(&path).map_err(|e| e.error)?;
+        }
+
+        remove_stale_jars(dir, name, &file_name);
+        Ok(path)
+    }
+
+    /// Get the process-wide JVM, starting it with
+    /// the default configuration if it is not yet running.
+    ///
+    /// # Errors
+    ///
+    /// If the JVM has not been started yet and starting it fails.
+    /// A subsequent call will retry starting the JVM.
+    pub fn global() -> Result<&'static Self, Error> {
+        if let Some(jvm) = GLOBAL_JVM.get() {
+            return Ok(jvm);
+        }
+
+        Self::start_global(&JvmConfig::default(), false)
+    }
+
+    /// Start the process-wide JVM with the provided configuration.
+    /// Must be called before anything else uses [JVM::global].
+    ///
+    /// # Errors
+    ///
+    /// - If the process-wide JVM is already running
+    /// - If starting the JVM fails
+    pub fn init_global(config: JvmConfig) -> Result<&'static Self, Error> {
+        Self::start_global(&config, true)
+    }
+
+    /// Start the process-wide JVM if it is not yet running.
+    ///
+    /// # Errors
+    ///
+    /// - If the JVM is already running and `fail_if_running` is set
+    /// - If starting the JVM fails
+    fn start_global(config: &JvmConfig, fail_if_running: bool) -> Result<&'static Self, Error> {
+        let _guard = GLOBAL_JVM_INIT
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(jvm) = GLOBAL_JVM.get() {
+            return if fail_if_running {
+                Err(Error::JvmAlreadyRunning)
+            } else {
+                Ok(jvm)
+            };
+        }
+
+        let jvm = Self::with_config(config)?;
+        Ok(GLOBAL_JVM.get_or_init(|| jvm))
+    }
+
     /// Attach the current thread to the JVM.
     /// The thread is detached when the returned AttachGuard is dropped.
     ///
@@ -65,3 +284,90 @@ impl JVM {
         Ok(self.jvm.attach_current_thread()?)
     }
 }
+
+/// A digest of the contents of a jar, which is stable across Rust releases and platforms (64-bit FNV-1a)
+fn jar_digest(contents: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    contents.iter().fold(OFFSET_BASIS, |digest, &byte| {
+        (digest ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Remove the jars named `{name}-{digest}.jar` from `dir`, except `current`.
+/// Failures are ignored: another process may still use a jar, or have removed it already.
+fn remove_stale_jars(dir: &Path, name: &str, current: &str) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let prefix = format!("{name}-");
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        let stale = file_name != current
+            && file_name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".jar"))
+                .is_some_and(|digest| {
+                    digest.len() == 16 && digest.bytes().all(|b| b.is_ascii_hexdigit())
+                });
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jar_digest_is_stable() {
+        // Reference values of 64-bit FNV-1a
+        assert_eq!(jar_digest(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(jar_digest(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(jar_digest(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn extracts_jar_once() {
+        let dir = TempDir::new().unwrap();
+        let path = JVM::extract_jar(dir.path(), "itext", b"jar v1").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"jar v1");
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+
+        assert_eq!(
+            JVM::extract_jar(dir.path(), "itext", b"jar v1").unwrap(),
+            path
+        );
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), modified);
+    }
+
+    #[test]
+    fn replaces_corrupted_jar_of_same_size() {
+        let dir = TempDir::new().unwrap();
+        let path = JVM::extract_jar(dir.path(), "itext", b"jar v1").unwrap();
+        fs::write(&path, b"jar v2").unwrap();
+
+        JVM::extract_jar(dir.path(), "itext", b"jar v1").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"jar v1");
+    }
+
+    #[test]
+    fn removes_stale_jars() {
+        let dir = TempDir::new().unwrap();
+        let old = JVM::extract_jar(dir.path(), "itext", b"jar v1").unwrap();
+        let logger = JVM::extract_jar(dir.path(), "logger", b"logger").unwrap();
+        let unrelated = dir.path().join("itext-extension.jar");
+        fs::write(&unrelated, b"extension").unwrap();
+
+        let new = JVM::extract_jar(dir.path(), "itext", b"jar v2").unwrap();
+        assert!(!old.exists());
+        assert!(new.exists());
+        assert!(logger.exists());
+        assert!(unrelated.exists());
+    }
+}
@@ -0,0 +1,332 @@
+//! Bindings to iText kernel functionality which the itext crate does not (yet) provide.
+
+use crate::PdfAConformanceLevel;
+use itext::itext::kernel::{PdfDocument, PdfWriter};
+use itext::itext::layout::Document;
+use itext::java::ByteArrayOutputStream;
+use jni::errors::Result;
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+/// The class of PDF/A documents, provided by iText's pdfa module
+const PDF_A_DOCUMENT_CLASS: &str = "com/itextpdf/pdfa/PdfADocument";
+
+/// The XMP namespace of the PDF/A identification schema
+const PDF_A_ID_NAMESPACE: &str = "http://www.aiim.org/pdfa/ns/id/";
+
+/// A file embedded in a document, see [PdfDocumentExt::add_associated_file]
+pub(crate) struct AssociatedFile<'b> {
+    /// The file name, e.g. `factur-x.xml`
+    pub name: &'b str,
+    pub description: &'b str,
+    /// The MIME type, e.g. `text/xml`
+    pub mime_type: &'b str,
+    /// How the file relates to the document, e.g. `Alternative` or `Source`
+    pub relationship: &'b str,
+    pub bytes: &'b [u8],
+}
+
+/// Additional bindings for `com.itextpdf.kernel.pdf.PdfDocument`
+pub(crate) trait PdfDocumentExt<'a>
+where
+    Self: AsRef<JObject<'a>>,
+{
+    /// Set an entry of the document information dictionary.
+    /// `setter` is the name of the setter in `com.itextpdf.kernel.pdf.PdfDocumentInfo`, e.g. `setTitle`.
+    fn set_document_info(&self, setter: &str, value: &str, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        let info = env
+            .call_method(
+                self.as_ref(),
+                "getDocumentInfo",
+                "()Lcom/itextpdf/kernel/pdf/PdfDocumentInfo;",
+                &[],
+            )?
+            .l()?;
+        let value = env.new_string(value)?;
+        env.call_method(
+            &info,
+            setter,
+            "(Ljava/lang/String;)Lcom/itextpdf/kernel/pdf/PdfDocumentInfo;",
+            &[(&value).into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Set the natural language of the document's text, as a BCP 47 language tag
+    fn set_language(&self, language: &str, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        let catalog = env
+            .call_method(
+                self.as_ref(),
+                "getCatalog",
+                "()Lcom/itextpdf/kernel/pdf/PdfCatalog;",
+                &[],
+            )?
+            .l()?;
+        let language = env.new_string(language)?;
+        let language = env.new_object(
+            "com/itextpdf/kernel/pdf/PdfString",
+            "(Ljava/lang/String;)V",
+            &[(&language).into()],
+        )?;
+        env.call_method(
+            &catalog,
+            "setLang",
+            "(Lcom/itextpdf/kernel/pdf/PdfString;)V",
+            &[(&language).into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Write XMP metadata to the document, identifying it as PDF/A if a conformance level is provided.
+    /// When the document is closed, iText fills the XMP metadata with the document information dictionary.
+    fn add_xmp_metadata(
+        &self,
+        pdf_a: Option<PdfAConformanceLevel>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<&Self> {
+        let xmp = env
+            .call_static_method(
+                "com/itextpdf/kernel/xmp/XMPMetaFactory",
+                "create",
+                "()Lcom/itextpdf/kernel/xmp/XMPMeta;",
+                &[],
+            )?
+            .l()?;
+
+        if let Some(level) = pdf_a {
+            let properties = [("part", level.part()), ("conformance", level.conformance())];
+            for (name, value) in properties {
+                let namespace = env.new_string(PDF_A_ID_NAMESPACE)?;
+                let name = env.new_string(name)?;
+                let value = env.new_string(value)?;
+                env.call_method(
+                    &xmp,
+                    "setProperty",
+                    "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/Object;)V",
+                    &[(&namespace).into(), (&name).into(), (&value).into()],
+                )?;
+            }
+        }
+
+        env.call_method(
+            self.as_ref(),
+            "setXmpMetadata",
+            "(Lcom/itextpdf/kernel/xmp/XMPMeta;)V",
+            &[(&xmp).into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Add the properties of an XMP packet to the document's XMP metadata,
+    /// replacing existing properties with the same name
+    fn append_xmp_metadata(&self, packet: &str, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        let current = env
+            .call_method(self.as_ref(), "getXmpMetadata", "()[B", &[])?
+            .l()?;
+        let current = env
+            .call_static_method(
+                "com/itextpdf/kernel/xmp/XMPMetaFactory",
+                "parseFromBuffer",
+                "([B)Lcom/itextpdf/kernel/xmp/XMPMeta;",
+                &[(&current).into()],
+            )?
+            .l()?;
+
+        let packet = env.new_string(packet)?;
+        let appended = env
+            .call_static_method(
+                "com/itextpdf/kernel/xmp/XMPMetaFactory",
+                "parseFromString",
+                "(Ljava/lang/String;)Lcom/itextpdf/kernel/xmp/XMPMeta;",
+                &[(&packet).into()],
+            )?
+            .l()?;
+
+        env.call_static_method(
+            "com/itextpdf/kernel/xmp/XMPUtils",
+            "appendProperties",
+            "(Lcom/itextpdf/kernel/xmp/XMPMeta;Lcom/itextpdf/kernel/xmp/XMPMeta;ZZ)V",
+            &[
+                (&appended).into(),
+                (&current).into(),
+                true.into(),
+                true.into(),
+            ],
+        )?;
+        env.call_method(
+            self.as_ref(),
+            "setXmpMetadata",
+            "(Lcom/itextpdf/kernel/xmp/XMPMeta;)V",
+            &[(&current).into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Embed a file which is associated with the entire document, as PDF/A-3 allows
+    fn add_associated_file(
+        &self,
+        file: &AssociatedFile<'_>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<&Self> {
+        let bytes = env.byte_array_from_slice(file.bytes)?;
+        let description = env.new_string(file.description)?;
+        let name = env.new_string(file.name)?;
+        let mime_type = pdf_name(file.mime_type, env)?;
+        let relationship = pdf_name(file.relationship, env)?;
+
+        // PDF/A-3 requires the modification date of embedded files
+        let parameters = env.new_object("com/itextpdf/kernel/pdf/PdfDictionary", "()V", &[])?;
+        let modification_date = env.new_object("com/itextpdf/kernel/pdf/PdfDate", "()V", &[])?;
+        let modification_date = env
+            .call_method(
+                &modification_date,
+                "getPdfObject",
+                "()Lcom/itextpdf/kernel/pdf/PdfObject;",
+                &[],
+            )?
+            .l()?;
+        let key = pdf_name("ModDate", env)?;
+        env.call_method(
+            &parameters,
+            "put",
+            "(Lcom/itextpdf/kernel/pdf/PdfName;Lcom/itextpdf/kernel/pdf/PdfObject;)Lcom/itextpdf/kernel/pdf/PdfObject;",
+            &[(&key).into(), (&modification_date).into()],
+        )?;
+
+        let file_spec = env
+            .call_static_method(
+                "com/itextpdf/kernel/pdf/filespec/PdfFileSpec",
+                "createEmbeddedFileSpec",
+                "(Lcom/itextpdf/kernel/pdf/PdfDocument;[BLjava/lang/String;Ljava/lang/String;Lcom/itextpdf/kernel/pdf/PdfName;Lcom/itextpdf/kernel/pdf/PdfDictionary;Lcom/itextpdf/kernel/pdf/PdfName;)Lcom/itextpdf/kernel/pdf/filespec/PdfFileSpec;",
+                &[
+                    self.as_ref().into(),
+                    (&bytes).into(),
+                    (&description).into(),
+                    (&name).into(),
+                    (&mime_type).into(),
+                    (&parameters).into(),
+                    (&relationship).into(),
+                ],
+            )?
+            .l()?;
+        env.call_method(
+            self.as_ref(),
+            "addAssociatedFile",
+            "(Ljava/lang/String;Lcom/itextpdf/kernel/pdf/filespec/PdfFileSpec;)V",
+            &[(&description).into(), (&file_spec).into()],
+        )?;
+        Ok(self)
+    }
+}
+
+impl<'a> PdfDocumentExt<'a> for PdfDocument<'a> {}
+
+/// Whether iText's pdfa module is on the classpath, see [crate::JvmConfig::classpath_jar]
+pub(crate) fn is_pdf_a_available(env: &mut JNIEnv<'_>) -> bool {
+    if env.find_class(PDF_A_DOCUMENT_CLASS).is_ok() {
+        return true;
+    }
+
+    // Clear the NoClassDefFoundError
+    let _ = env.exception_clear();
+    false
+}
+
+/// Create a document which iText checks for conformance to `level` while it is written,
+/// with an sRGB output intent. Violations throw a `com.itextpdf.pdfa.PdfAConformanceException`.
+/// iText's pdfa module must be on the classpath, see [is_pdf_a_available].
+pub(crate) fn new_pdf_a_document<'a>(
+    writer: &PdfWriter<'a>,
+    level: PdfAConformanceLevel,
+    env: &mut JNIEnv<'a>,
+) -> Result<PdfDocument<'a>> {
+    let conformance_level = env
+        .get_static_field(
+            "com/itextpdf/kernel/pdf/PdfAConformanceLevel",
+            level.java_constant(),
+            "Lcom/itextpdf/kernel/pdf/PdfAConformanceLevel;",
+        )?
+        .l()?;
+    let output_intent = srgb_output_intent(env)?;
+
+    let document = env.new_object(
+        PDF_A_DOCUMENT_CLASS,
+        "(Lcom/itextpdf/kernel/pdf/PdfWriter;Lcom/itextpdf/kernel/pdf/PdfAConformanceLevel;Lcom/itextpdf/kernel/pdf/PdfOutputIntent;)V",
+        &[
+            writer.as_ref().into(),
+            (&conformance_level).into(),
+            (&output_intent).into(),
+        ],
+    )?;
+
+    wrap_pdf_document(&document, env)
+}
+
+/// Wrap a `com.itextpdf.kernel.pdf.PdfDocument`, or an instance of a subclass, in the itext crate's [PdfDocument].
+///
+/// The itext crate only wraps documents it creates itself, or the document of a layout [Document].
+/// So a layout document of a placeholder is pointed at the object, after which its document is wrapped.
+/// The placeholder is written to a stream of its own and discarded.
+fn wrap_pdf_document<'a>(object: &JObject<'a>, env: &mut JNIEnv<'a>) -> Result<PdfDocument<'a>> {
+    let placeholder_stream = ByteArrayOutputStream::new(env)?;
+    let placeholder = PdfDocument::new(&PdfWriter::new(&placeholder_stream, env)?, env)?;
+    let document = Document::new(&placeholder, env)?;
+
+    // Declared by `com.itextpdf.layout.RootElement`
+    env.set_field(
+        &document,
+        "pdfDocument",
+        "Lcom/itextpdf/kernel/pdf/PdfDocument;",
+        object.into(),
+    )?;
+
+    document.get_pdf_document(env)
+}
+
+/// Create an output intent with the sRGB color profile shipped with the JVM,
+/// which defines how the document's colors should be reproduced
+fn srgb_output_intent<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>> {
+    let color_space = env
+        .get_static_field("java/awt/color/ColorSpace", "CS_sRGB", "I")?
+        .i()?;
+    let profile = env
+        .call_static_method(
+            "java/awt/color/ICC_Profile",
+            "getInstance",
+            "(I)Ljava/awt/color/ICC_Profile;",
+            &[color_space.into()],
+        )?
+        .l()?;
+    let profile = env.call_method(&profile, "getData", "()[B", &[])?.l()?;
+    let profile = env.new_object(
+        "java/io/ByteArrayInputStream",
+        "([B)V",
+        &[(&profile).into()],
+    )?;
+
+    let identifier = env.new_string("Custom")?;
+    let condition = env.new_string("")?;
+    let registry = env.new_string("http://www.color.org")?;
+    let info = env.new_string("sRGB IEC61966-2.1")?;
+    env.new_object(
+        "com/itextpdf/kernel/pdf/PdfOutputIntent",
+        "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/io/InputStream;)V",
+        &[
+            (&identifier).into(),
+            (&condition).into(),
+            (&registry).into(),
+            (&info).into(),
+            (&profile).into(),
+        ],
+    )
+}
+
+/// Create a `com.itextpdf.kernel.pdf.PdfName`
+fn pdf_name<'a>(name: &str, env: &mut JNIEnv<'a>) -> Result<JObject<'a>> {
+    let name = env.new_string(name)?;
+    env.new_object(
+        "com/itextpdf/kernel/pdf/PdfName",
+        "(Ljava/lang/String;)V",
+        &[(&name).into()],
+    )
+}
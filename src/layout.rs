@@ -0,0 +1,324 @@
+//! Bindings to iText layout functionality which the itext crate does not (yet) provide.
+
+use itext::itext::kernel::Rectangle;
+use itext::itext::layout::{
+    BlockElement, Canvas, Cell, Document, ElementPropertyContainer, RootElement, Table,
+};
+use itext::java_object;
+use jni::errors::Result;
+use jni::objects::{JObject, JString};
+use jni::sys::jsize;
+use jni::JNIEnv;
+
+java_object!(FontProvider);
+
+/// The height of the area used to measure elements, large enough for any element to fit
+const MEASURE_AREA_HEIGHT: f32 = 100_000.0;
+
+/// Additional bindings for `com.itextpdf.layout.element.Table`
+pub(crate) trait TableExt<'a>
+where
+    Self: AsRef<JObject<'a>>,
+{
+    /// Add a cell to the header, which is repeated on every page the table spans
+    fn add_header_cell(&self, cell: &Cell<'a>, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        env.call_method(
+            self.as_ref(),
+            "addHeaderCell",
+            "(Lcom/itextpdf/layout/element/Cell;)Lcom/itextpdf/layout/element/Table;",
+            &[cell.as_ref().into()],
+        )?;
+        Ok(self)
+    }
+}
+
+impl<'a> TableExt<'a> for Table<'a> {}
+
+/// Additional bindings for `com.itextpdf.layout.Canvas`
+pub(crate) trait CanvasExt<'a>
+where
+    Self: AsRef<JObject<'a>>,
+{
+    /// Close the canvas, finishing the content drawn on it
+    fn close(&self, env: &mut JNIEnv<'a>) -> Result<()> {
+        env.call_method(self.as_ref(), "close", "()V", &[])?;
+        Ok(())
+    }
+}
+
+impl<'a> CanvasExt<'a> for Canvas<'a> {}
+
+/// Additional bindings for `com.itextpdf.layout.element.BlockElement`
+pub(crate) trait BlockElementExt<'a>: BlockElement<'a> {
+    /// Whether the element should be moved to the next page instead of being split
+    fn set_keep_together(&self, keep_together: bool, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        env.call_method(
+            self.as_ref(),
+            "setKeepTogether",
+            "(Z)Lcom/itextpdf/layout/element/IElement;",
+            &[keep_together.into()],
+        )?;
+        Ok(self)
+    }
+}
+
+impl<'a, T: BlockElement<'a>> BlockElementExt<'a> for T {}
+
+/// The faces a document's font family provides besides the regular face
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FontFaces {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// Additional bindings for `com.itextpdf.layout.ElementPropertyContainer`
+pub(crate) trait ElementPropertyContainerExt<'a>: ElementPropertyContainer<'a> {
+    /// Draw the text using the bold face of the font family.
+    /// If the family has no bold face, iText simulates it.
+    fn set_bold_face(&self, faces: FontFaces, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        if faces.bold {
+            set_string_property(self.as_ref(), "FONT_WEIGHT", "bold", env)?;
+            Ok(self)
+        } else {
+            self.set_bold(env)
+        }
+    }
+
+    /// Draw the text using the italic face of the font family.
+    /// If the family has no italic face, iText simulates it.
+    fn set_italic_face(&self, faces: FontFaces, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        if faces.italic {
+            set_string_property(self.as_ref(), "FONT_STYLE", "italic", env)?;
+            Ok(self)
+        } else {
+            self.set_italic(env)
+        }
+    }
+}
+
+impl<'a, T: ElementPropertyContainer<'a>> ElementPropertyContainerExt<'a> for T {}
+
+/// Set a property of an element to a string value.
+/// `property` is the name of a constant in `com.itextpdf.layout.property.Property`.
+fn set_string_property<'a>(
+    element: &JObject<'a>,
+    property: &str,
+    value: &str,
+    env: &mut JNIEnv<'a>,
+) -> Result<()> {
+    let property = env
+        .get_static_field("com/itextpdf/layout/property/Property", property, "I")?
+        .i()?;
+    let value = env.new_string(value)?;
+    env.call_method(
+        element,
+        "setProperty",
+        "(ILjava/lang/Object;)V",
+        &[property.into(), (&value).into()],
+    )?;
+    Ok(())
+}
+
+/// Additional bindings for `com.itextpdf.layout.Document`
+pub(crate) trait DocumentExt<'a>
+where
+    Self: AsRef<JObject<'a>>,
+{
+    fn get_top_margin(&self, env: &mut JNIEnv<'a>) -> Result<f32> {
+        env.call_method(self.as_ref(), "getTopMargin", "()F", &[])?
+            .f()
+    }
+
+    /// Continue the content on a new page
+    fn add_area_break(&self, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        let area_break = env.new_object("com/itextpdf/layout/element/AreaBreak", "()V", &[])?;
+        env.call_method(
+            self.as_ref(),
+            "add",
+            "(Lcom/itextpdf/layout/element/AreaBreak;)Lcom/itextpdf/layout/Document;",
+            &[(&area_break).into()],
+        )?;
+        Ok(self)
+    }
+
+    /// The height left for content on the current page
+    fn get_remaining_height(&self, env: &mut JNIEnv<'a>) -> Result<f32> {
+        let renderer = root_renderer(self.as_ref(), env)?;
+        let area = env
+            .call_method(
+                &renderer,
+                "getCurrentArea",
+                "()Lcom/itextpdf/layout/layout/LayoutArea;",
+                &[],
+            )?
+            .l()?;
+        area_height(&area, env)
+    }
+
+    /// The height of an element when it is laid out in this document with the provided width.
+    /// Elements inherit the document's properties, e.g. its font.
+    fn measure_height<E: BlockElement<'a>>(
+        &self,
+        element: &E,
+        width: f32,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<f32> {
+        let parent = root_renderer(self.as_ref(), env)?;
+        let renderer = env
+            .call_method(
+                element.as_ref(),
+                "createRendererSubTree",
+                "()Lcom/itextpdf/layout/renderer/IRenderer;",
+                &[],
+            )?
+            .l()?;
+        let renderer = env
+            .call_method(
+                &renderer,
+                "setParent",
+                "(Lcom/itextpdf/layout/renderer/IRenderer;)Lcom/itextpdf/layout/renderer/IRenderer;",
+                &[(&parent).into()],
+            )?
+            .l()?;
+
+        let bbox = Rectangle::new_w_h(width, MEASURE_AREA_HEIGHT, env)?;
+        let area = env.new_object(
+            "com/itextpdf/layout/layout/LayoutArea",
+            "(ILcom/itextpdf/kernel/geom/Rectangle;)V",
+            &[1.into(), bbox.as_ref().into()],
+        )?;
+        let context = env.new_object(
+            "com/itextpdf/layout/layout/LayoutContext",
+            "(Lcom/itextpdf/layout/layout/LayoutArea;)V",
+            &[(&area).into()],
+        )?;
+
+        let result = env
+            .call_method(
+                &renderer,
+                "layout",
+                "(Lcom/itextpdf/layout/layout/LayoutContext;)Lcom/itextpdf/layout/layout/LayoutResult;",
+                &[(&context).into()],
+            )?
+            .l()?;
+        let occupied = env
+            .call_method(
+                &result,
+                "getOccupiedArea",
+                "()Lcom/itextpdf/layout/layout/LayoutArea;",
+                &[],
+            )?
+            .l()?;
+        area_height(&occupied, env)
+    }
+}
+
+impl<'a> DocumentExt<'a> for Document<'a> {}
+
+/// Additional bindings for `com.itextpdf.layout.RootElement`
+pub(crate) trait RootElementExt<'a>: RootElement<'a> {
+    /// Use the fonts of the provider for all content
+    fn set_font_provider(
+        &self,
+        provider: &FontProvider<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<&Self> {
+        env.call_method(
+            self.as_ref(),
+            "setFontProvider",
+            "(Lcom/itextpdf/layout/font/FontProvider;)V",
+            &[provider.as_ref().into()],
+        )?;
+        Ok(self)
+    }
+
+    /// Use the font families of the font provider, in order of preference.
+    /// Characters missing from a font are drawn using the next family containing them.
+    fn set_font_family(&self, families: &[String], env: &mut JNIEnv<'a>) -> Result<&Self> {
+        let array =
+            env.new_object_array(families.len() as jsize, "java/lang/String", JObject::null())?;
+        for (idx, family) in families.iter().enumerate() {
+            let family = env.new_string(family)?;
+            env.set_object_array_element(&array, idx as jsize, family)?;
+        }
+
+        env.call_method(
+            self.as_ref(),
+            "setFontFamily",
+            "([Ljava/lang/String;)Lcom/itextpdf/layout/IPropertyContainer;",
+            &[(&array).into()],
+        )?;
+        Ok(self)
+    }
+}
+
+impl<'a, T: RootElement<'a>> RootElementExt<'a> for T {}
+
+impl<'a> FontProvider<'a> {
+    pub fn new(env: &mut JNIEnv<'a>) -> Result<Self> {
+        let obj = env.new_object("com/itextpdf/layout/font/FontProvider", "()V", &[])?;
+        Ok(Self(obj))
+    }
+
+    /// Add a TrueType or OpenType font, embedded using Identity-H encoding.
+    /// Returns the font's family name, in lowercase.
+    pub fn add_font(&self, bytes: &[u8], env: &mut JNIEnv<'a>) -> Result<String> {
+        let byte_array = env.byte_array_from_slice(bytes)?;
+        let encoding = env.new_string("Identity-H")?;
+        env.call_method(
+            self.as_ref(),
+            "addFont",
+            "([BLjava/lang/String;)Z",
+            &[(&byte_array).into(), (&encoding).into()],
+        )?;
+
+        let descriptor = env
+            .call_static_method(
+                "com/itextpdf/io/font/FontProgramDescriptorFactory",
+                "fetchDescriptor",
+                "([B)Lcom/itextpdf/io/font/FontProgramDescriptor;",
+                &[(&byte_array).into()],
+            )?
+            .l()?;
+        let family = env
+            .call_method(
+                &descriptor,
+                "getFamilyNameLowerCase",
+                "()Ljava/lang/String;",
+                &[],
+            )?
+            .l()?;
+        let family = env.get_string(&JString::from(family))?.into();
+        Ok(family)
+    }
+
+    /// Add the 14 standard PDF fonts, e.g. Helvetica
+    pub fn add_standard_pdf_fonts(&self, env: &mut JNIEnv<'a>) -> Result<&Self> {
+        env.call_method(self.as_ref(), "addStandardPdfFonts", "()I", &[])?;
+        Ok(self)
+    }
+}
+
+/// Get the renderer of a document
+fn root_renderer<'a>(document: &JObject<'a>, env: &mut JNIEnv<'a>) -> Result<JObject<'a>> {
+    env.call_method(
+        document,
+        "getRenderer",
+        "()Lcom/itextpdf/layout/renderer/IRenderer;",
+        &[],
+    )?
+    .l()
+}
+
+/// Get the height of a `LayoutArea`'s bounding box
+fn area_height<'a>(area: &JObject<'a>, env: &mut JNIEnv<'a>) -> Result<f32> {
+    let bbox = env
+        .call_method(
+            area,
+            "getBBox",
+            "()Lcom/itextpdf/kernel/geom/Rectangle;",
+            &[],
+        )?
+        .l()?;
+    env.call_method(&bbox, "getHeight", "()F", &[])?.f()
+}
@@ -1,14 +1,40 @@
+#[cfg(feature = "async")]
+mod async_pool;
+mod barcode;
+mod epc;
 mod error;
+mod factur_x;
 pub mod footer;
 pub mod header;
 pub mod invoice;
 mod jvm;
+mod kernel;
+mod layout;
+mod locale;
+mod metadata;
+mod money;
 pub mod packing_slip;
+mod page;
+mod page_numbers;
+mod pagination;
+mod pdf_a;
+mod pool;
 mod render_target;
+mod ubl;
+mod validate;
+mod xml;
 
 pub use error::*;
 pub use jvm::*;
+pub use locale::*;
+pub use metadata::*;
+pub use money::*;
+pub use page::*;
+pub use page_numbers::*;
+pub use pdf_a::*;
+pub use pool::*;
 pub use render_target::*;
+pub use validate::*;
 
 /// Something which can be rendered to a PDF target
 pub trait PdfRenderable {
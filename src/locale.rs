@@ -0,0 +1,444 @@
+use crate::Decimal;
+pub use chrono::NaiveDate;
+use std::collections::HashMap;
+
+/// The language and formatting conventions used on a document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// nl-NL, e.g. `1.234,56` and `19-06-2023`
+    #[default]
+    Dutch,
+    /// en-GB, e.g. `1,234.56` and `19/06/2023`
+    English,
+    /// de-DE, e.g. `1.234,56` and `19.06.2023`
+    German,
+    /// fr-FR, e.g. `1 234,56` and `19/06/2023`
+    French,
+}
+
+impl Locale {
+    /// The BCP 47 language tag, e.g. `nl-NL`
+    pub fn language_tag(self) -> &'static str {
+        match self {
+            Self::Dutch => "nl-NL",
+            Self::English => "en-GB",
+            Self::German => "de-DE",
+            Self::French => "fr-FR",
+        }
+    }
+
+    /// The separator between the integer and fractional part of a number
+    pub fn decimal_separator(self) -> char {
+        match self {
+            Self::English => '.',
+            Self::Dutch | Self::German | Self::French => ',',
+        }
+    }
+
+    /// The separator between groups of thousands
+    pub fn grouping_separator(self) -> char {
+        match self {
+            Self::English => ',',
+            Self::Dutch | Self::German => '.',
+            // No-break space, so numbers are never wrapped
+            Self::French => '\u{a0}',
+        }
+    }
+
+    /// The `strftime`-like pattern used to format dates
+    pub fn date_pattern(self) -> &'static str {
+        match self {
+            Self::Dutch => "%d-%m-%Y",
+            Self::English | Self::French => "%d/%m/%Y",
+            Self::German => "%d.%m.%Y",
+        }
+    }
+
+    /// Format a number with exactly the provided amount of decimals.
+    /// The number should already be rounded to that amount of decimals.
+    pub fn format_decimal(self, value: Decimal, decimals: u32) -> String {
+        let mut value = value;
+        value.rescale(decimals);
+
+        let digits = value.abs().to_string();
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        let mut formatted = String::new();
+        if value.is_sign_negative() && !value.is_zero() {
+            formatted.push('-');
+        }
+
+        for (idx, digit) in integer.chars().enumerate() {
+            if idx > 0 && (integer.len() - idx) % 3 == 0 {
+                formatted.push(self.grouping_separator());
+            }
+            formatted.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            formatted.push(self.decimal_separator());
+            formatted.push_str(fraction);
+        }
+
+        formatted
+    }
+
+    /// Format a date using the locale's date pattern
+    pub fn format_date(self, date: NaiveDate) -> String {
+        date.format(self.date_pattern()).to_string()
+    }
+}
+
+/// A fixed text printed on a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Label {
+    /// Label before the order the document concerns
+    Concerning,
+    /// The reference on an invoice
+    Reference,
+    /// The seller's reference on a packing slip
+    OurReference,
+    /// Prefix before the order ID, e.g. 'Order #:'
+    OrderIdPrefix,
+    OrderId,
+    InvoiceDate,
+    ExpirationDate,
+    InvoiceId,
+    ArticleId,
+    Article,
+    Description,
+    Quantity,
+    Price,
+    Discount,
+    /// The price per unit taking into account the discount
+    ItemSubtotal,
+    /// The total price of an item
+    ItemTotal,
+    TotalExcludingVat,
+    TotalVat,
+    /// The total price to be paid including VAT
+    TotalPayable,
+    VatRate,
+    /// The amount over which VAT is computed
+    VatBase,
+    VatAmount,
+    VatExempt,
+    VatReverseCharge,
+    QuantityOrdered,
+    QuantityDelivered,
+    QuantityBackorder,
+    PackingSlipId,
+    DeliveryDate,
+    /// Prefix before the name of the department, e.g. 'Attn.'
+    DepartmentPrefix,
+    /// The subtotal at the bottom of a page, carried to the next page
+    CarriedForward,
+    /// The subtotal at the top of a page, carried from the previous page
+    BroughtForward,
+    /// Notice at the bottom of a page that a table continues on the next page
+    ContinuedOnNextPage,
+    /// The page number, see [crate::PageNumbering::format] for the placeholders
+    PageNumber,
+    /// Caption of the QR code customers can scan to pay an invoice
+    ScanToPay,
+    /// Header of the column with barcodes of the articles
+    Barcode,
+}
+
+impl Label {
+    /// The built-in translation of the label
+    pub fn builtin(self, locale: Locale) -> &'static str {
+        use Locale::*;
+
+        match (self, locale) {
+            (Self::Concerning, Dutch) => "Betreft",
+            (Self::Concerning, English) => "Concerning",
+            (Self::Concerning, German) => "Betreff",
+            (Self::Concerning, French) => "Objet",
+
+            (Self::Reference, Dutch) => "Referentie",
+            (Self::Reference, English) => "Reference",
+            (Self::Reference, German) => "Referenz",
+            (Self::Reference, French) => "Référence",
+
+            (Self::OurReference, Dutch) => "Onze referentie",
+            (Self::OurReference, English) => "Our reference",
+            (Self::OurReference, German) => "Unsere Referenz",
+            (Self::OurReference, French) => "Notre référence",
+
+            (Self::OrderIdPrefix, Dutch) => "Bestelling #:",
+            (Self::OrderIdPrefix, English) => "Order #:",
+            (Self::OrderIdPrefix, German) => "Bestellung #:",
+            (Self::OrderIdPrefix, French) => "Commande n° :",
+
+            (Self::OrderId, Dutch) => "Order nummer",
+            (Self::OrderId, English) => "Order number",
+            (Self::OrderId, German) => "Bestellnummer",
+            (Self::OrderId, French) => "Numéro de commande",
+
+            (Self::InvoiceDate, Dutch) => "Factuurdatum",
+            (Self::InvoiceDate, English) => "Invoice date",
+            (Self::InvoiceDate, German) => "Rechnungsdatum",
+            (Self::InvoiceDate, French) => "Date de facture",
+
+            (Self::ExpirationDate, Dutch) => "Vervaldatum",
+            (Self::ExpirationDate, English) => "Due date",
+            (Self::ExpirationDate, German) => "Fälligkeitsdatum",
+            (Self::ExpirationDate, French) => "Date d'échéance",
+
+            (Self::InvoiceId, Dutch) => "Factuurnummer",
+            (Self::InvoiceId, English) => "Invoice number",
+            (Self::InvoiceId, German) => "Rechnungsnummer",
+            (Self::InvoiceId, French) => "Numéro de facture",
+
+            (Self::ArticleId, Dutch) => "Artikelnummer",
+            (Self::ArticleId, English) => "Article number",
+            (Self::ArticleId, German) => "Artikelnummer",
+            (Self::ArticleId, French) => "Référence article",
+
+            (Self::Article, Dutch) => "Artikel",
+            (Self::Article, English) => "Article",
+            (Self::Article, German) => "Artikel",
+            (Self::Article, French) => "Article",
+
+            (Self::Description, Dutch) => "Omschrijving",
+            (Self::Description, English) => "Description",
+            (Self::Description, German) => "Beschreibung",
+            (Self::Description, French) => "Description",
+
+            (Self::Quantity, Dutch) => "Aantal",
+            (Self::Quantity, English) => "Quantity",
+            (Self::Quantity, German) => "Menge",
+            (Self::Quantity, French) => "Quantité",
+
+            (Self::Price, Dutch) => "Prijs",
+            (Self::Price, English) => "Price",
+            (Self::Price, German) => "Preis",
+            (Self::Price, French) => "Prix",
+
+            (Self::Discount, Dutch) => "Korting",
+            (Self::Discount, English) => "Discount",
+            (Self::Discount, German) => "Rabatt",
+            (Self::Discount, French) => "Remise",
+
+            (Self::ItemSubtotal, Dutch) => "Bedrag",
+            (Self::ItemSubtotal, English) => "Amount",
+            (Self::ItemSubtotal, German) => "Betrag",
+            (Self::ItemSubtotal, French) => "Montant",
+
+            (Self::ItemTotal, Dutch) => "Totaal",
+            (Self::ItemTotal, English) => "Total",
+            (Self::ItemTotal, German) => "Gesamt",
+            (Self::ItemTotal, French) => "Total",
+
+            (Self::TotalExcludingVat, Dutch) => "Totaal excl. BTW",
+            (Self::TotalExcludingVat, English) => "Total excl. VAT",
+            (Self::TotalExcludingVat, German) => "Summe netto",
+            (Self::TotalExcludingVat, French) => "Total HT",
+
+            (Self::TotalVat, Dutch) => "BTW",
+            (Self::TotalVat, English) => "VAT",
+            (Self::TotalVat, German) => "MwSt.",
+            (Self::TotalVat, French) => "TVA",
+
+            (Self::TotalPayable, Dutch) => "Totaal te voldoen",
+            (Self::TotalPayable, English) => "Total due",
+            (Self::TotalPayable, German) => "Zu zahlender Betrag",
+            (Self::TotalPayable, French) => "Total à payer",
+
+            (Self::VatRate, Dutch) => "BTW-tarief",
+            (Self::VatRate, English) => "VAT rate",
+            (Self::VatRate, German) => "MwSt.-Satz",
+            (Self::VatRate, French) => "Taux de TVA",
+
+            (Self::VatBase, Dutch) => "Grondslag",
+            (Self::VatBase, English) => "Taxable amount",
+            (Self::VatBase, German) => "Bemessungsgrundlage",
+            (Self::VatBase, French) => "Base HT",
+
+            (Self::VatAmount, Dutch) => "BTW",
+            (Self::VatAmount, English) => "VAT",
+            (Self::VatAmount, German) => "MwSt.",
+            (Self::VatAmount, French) => "TVA",
+
+            (Self::VatExempt, Dutch) => "Vrijgesteld",
+            (Self::VatExempt, English) => "Exempt",
+            (Self::VatExempt, German) => "Steuerfrei",
+            (Self::VatExempt, French) => "Exonéré",
+
+            (Self::VatReverseCharge, Dutch) => "BTW verlegd",
+            (Self::VatReverseCharge, English) => "Reverse charge",
+            (Self::VatReverseCharge, German) => "Reverse Charge",
+            (Self::VatReverseCharge, French) => "Autoliquidation",
+
+            (Self::QuantityOrdered, Dutch) => "Aantal besteld",
+            (Self::QuantityOrdered, English) => "Quantity ordered",
+            (Self::QuantityOrdered, German) => "Menge bestellt",
+            (Self::QuantityOrdered, French) => "Quantité commandée",
+
+            (Self::QuantityDelivered, Dutch) => "Aantal geleverd",
+            (Self::QuantityDelivered, English) => "Quantity delivered",
+            (Self::QuantityDelivered, German) => "Menge geliefert",
+            (Self::QuantityDelivered, French) => "Quantité livrée",
+
+            (Self::QuantityBackorder, Dutch) => "Aantal backorder",
+            (Self::QuantityBackorder, English) => "Quantity on backorder",
+            (Self::QuantityBackorder, German) => "Menge im Rückstand",
+            (Self::QuantityBackorder, French) => "Quantité en attente",
+
+            (Self::PackingSlipId, Dutch) => "Pakbonnummer",
+            (Self::PackingSlipId, English) => "Packing slip number",
+            (Self::PackingSlipId, German) => "Lieferscheinnummer",
+            (Self::PackingSlipId, French) => "Numéro de bon de livraison",
+
+            (Self::DeliveryDate, Dutch) => "Afleverdatum",
+            (Self::DeliveryDate, English) => "Delivery date",
+            (Self::DeliveryDate, German) => "Lieferdatum",
+            (Self::DeliveryDate, French) => "Date de livraison",
+
+            (Self::DepartmentPrefix, Dutch) => "T.a.v",
+            (Self::DepartmentPrefix, English) => "Attn.",
+            (Self::DepartmentPrefix, German) => "z. Hd.",
+            (Self::DepartmentPrefix, French) => "À l'attention de",
+
+            (Self::CarriedForward, Dutch) => "Transport",
+            (Self::CarriedForward, English) => "Carried forward",
+            (Self::CarriedForward, German) => "Übertrag",
+            (Self::CarriedForward, French) => "À reporter",
+
+            (Self::BroughtForward, Dutch) => "Transport",
+            (Self::BroughtForward, English) => "Brought forward",
+            (Self::BroughtForward, German) => "Übertrag",
+            (Self::BroughtForward, French) => "Report",
+
+            (Self::ContinuedOnNextPage, Dutch) => "Vervolg op volgende pagina",
+            (Self::ContinuedOnNextPage, English) => "Continued on next page",
+            (Self::ContinuedOnNextPage, German) => "Fortsetzung auf der nächsten Seite",
+            (Self::ContinuedOnNextPage, French) => "Suite à la page suivante",
+
+            (Self::PageNumber, Dutch) => "Pagina {page} van {pages}",
+            (Self::PageNumber, English) => "Page {page} of {pages}",
+            (Self::PageNumber, German) => "Seite {page} von {pages}",
+            (Self::PageNumber, French) => "Page {page} sur {pages}",
+
+            (Self::ScanToPay, Dutch) => "Scan om te betalen",
+            (Self::ScanToPay, English) => "Scan to pay",
+            (Self::ScanToPay, German) => "Scannen und bezahlen",
+            (Self::ScanToPay, French) => "Scanner pour payer",
+
+            (Self::Barcode, Dutch) => "Barcode",
+            (Self::Barcode, English) => "Barcode",
+            (Self::Barcode, German) => "Strichcode",
+            (Self::Barcode, French) => "Code-barres",
+        }
+    }
+}
+
+/// The texts used for the labels on a document.
+/// Custom translations take precedence over the built-in translations of the locale.
+///
+/// ```
+/// # use order_pdf_printer::{Label, Locale, Translations};
+/// let translations = Translations::new(Locale::English)
+///     .with(Label::ExpirationDate, "Payable before");
+/// assert_eq!(translations.get(Label::ExpirationDate), "Payable before");
+/// assert_eq!(translations.get(Label::InvoiceDate), "Invoice date");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Translations {
+    /// The locale of the built-in translations
+    pub locale: Locale,
+    /// Custom translations
+    catalog: HashMap<Label, String>,
+}
+
+impl Translations {
+    /// Use the built-in translations of the locale
+    pub fn new(locale: Locale) -> Self {
+        Self {
+            locale,
+            catalog: HashMap::new(),
+        }
+    }
+
+    /// Use a custom translation for a label
+    pub fn with(mut self, label: Label, text: impl Into<String>) -> Self {
+        self.catalog.insert(label, text.into());
+        self
+    }
+
+    /// Use custom translations for multiple labels, e.g. a catalog for a language
+    /// which has no built-in translations.
+    pub fn with_catalog<I, S>(mut self, catalog: I) -> Self
+    where
+        I: IntoIterator<Item = (Label, S)>,
+        S: Into<String>,
+    {
+        self.catalog.extend(
+            catalog
+                .into_iter()
+                .map(|(label, text)| (label, text.into())),
+        );
+        self
+    }
+
+    /// Get the text for a label
+    pub fn get(&self, label: Label) -> &str {
+        self.catalog
+            .get(&label)
+            .map(String::as_str)
+            .unwrap_or_else(|| label.builtin(self.locale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn groups_thousands() {
+        let value = dec!(1234567.89);
+        assert_eq!(Locale::Dutch.format_decimal(value, 2), "1.234.567,89");
+        assert_eq!(Locale::English.format_decimal(value, 2), "1,234,567.89");
+        assert_eq!(Locale::German.format_decimal(value, 2), "1.234.567,89");
+        assert_eq!(
+            Locale::French.format_decimal(value, 2),
+            "1\u{a0}234\u{a0}567,89"
+        );
+        assert_eq!(Locale::Dutch.format_decimal(dec!(1234.56), 2), "1.234,56");
+        assert_eq!(Locale::English.format_decimal(dec!(1234.56), 2), "1,234.56");
+    }
+
+    #[test]
+    fn does_not_group_below_thousand() {
+        assert_eq!(Locale::Dutch.format_decimal(dec!(999.99), 2), "999,99");
+        assert_eq!(Locale::English.format_decimal(dec!(0.5), 2), "0.50");
+        assert_eq!(Locale::Dutch.format_decimal(dec!(1000), 2), "1.000,00");
+    }
+
+    #[test]
+    fn formats_negative_values() {
+        assert_eq!(Locale::Dutch.format_decimal(dec!(-1234.5), 2), "-1.234,50");
+        assert_eq!(Locale::English.format_decimal(dec!(-12.34), 2), "-12.34");
+        assert_eq!(Locale::Dutch.format_decimal(dec!(-100000), 0), "-100.000");
+        // Zero has no sign
+        assert_eq!(Locale::English.format_decimal(dec!(-0.00), 2), "0.00");
+    }
+
+    #[test]
+    fn formats_without_decimals() {
+        assert_eq!(Locale::Dutch.format_decimal(dec!(1234), 0), "1.234");
+        assert_eq!(Locale::English.format_decimal(dec!(21), 0), "21");
+        assert_eq!(Locale::English.format_decimal(dec!(0), 0), "0");
+    }
+
+    #[test]
+    fn formats_dates() {
+        let date = NaiveDate::from_ymd_opt(2023, 6, 9).unwrap();
+        assert_eq!(Locale::Dutch.format_date(date), "09-06-2023");
+        assert_eq!(Locale::English.format_date(date), "09/06/2023");
+        assert_eq!(Locale::German.format_date(date), "09.06.2023");
+        assert_eq!(Locale::French.format_date(date), "09/06/2023");
+    }
+}
@@ -0,0 +1,52 @@
+/// Metadata stored in the document information dictionary and the XMP metadata of the PDF,
+/// e.g. for indexing by a document management system
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentMetadata {
+    /// The title, e.g. 'Invoice 230307'
+    pub title: Option<String>,
+    /// The person or company who created the document's content
+    pub author: Option<String>,
+    /// The subject of the document
+    pub subject: Option<String>,
+    /// Keywords associated with the document, e.g. the order ID
+    pub keywords: Vec<String>,
+    /// The application which created the document's content
+    pub creator: Option<String>,
+    /// The natural language of the document's text, as a BCP 47 language tag, e.g. `nl-NL`
+    pub language: Option<String>,
+}
+
+impl DocumentMetadata {
+    /// Use the values of `defaults` for the fields which are not set
+    ///
+    /// ```
+    /// # use order_pdf_printer::DocumentMetadata;
+    /// let configured = DocumentMetadata {
+    ///     author: Some("Accounting".to_string()),
+    ///     ..DocumentMetadata::default()
+    /// };
+    /// let defaults = DocumentMetadata {
+    ///     title: Some("Invoice 230307".to_string()),
+    ///     author: Some("Seller".to_string()),
+    ///     ..DocumentMetadata::default()
+    /// };
+    ///
+    /// let metadata = configured.or(defaults);
+    /// assert_eq!(metadata.title.as_deref(), Some("Invoice 230307"));
+    /// assert_eq!(metadata.author.as_deref(), Some("Accounting"));
+    /// ```
+    pub fn or(self, defaults: DocumentMetadata) -> Self {
+        Self {
+            title: self.title.or(defaults.title),
+            author: self.author.or(defaults.author),
+            subject: self.subject.or(defaults.subject),
+            keywords: if self.keywords.is_empty() {
+                defaults.keywords
+            } else {
+                self.keywords
+            },
+            creator: self.creator.or(defaults.creator),
+            language: self.language.or(defaults.language),
+        }
+    }
+}
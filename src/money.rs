@@ -0,0 +1,263 @@
+pub use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+use std::fmt;
+
+/// How monetary amounts are rounded when they are computed or printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round midpoints away from zero, i.e. `0.125` becomes `0.13`
+    #[default]
+    HalfUp,
+    /// Round midpoints to the nearest even number, i.e. `0.125` becomes `0.12`.
+    /// Also known as banker's rounding.
+    HalfEven,
+}
+
+impl Rounding {
+    /// Round an amount to the provided amount of decimals
+    pub fn round(self, amount: Decimal, decimals: u32) -> Decimal {
+        let strategy = match self {
+            Self::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Self::HalfEven => RoundingStrategy::MidpointNearestEven,
+        };
+
+        amount.round_dp_with_strategy(decimals, strategy)
+    }
+}
+
+/// Where the currency symbol is placed relative to an amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolPlacement {
+    /// Before the amount, e.g. `€ 12.50`
+    Prefix,
+    /// After the amount, e.g. `12.50 kr`
+    Suffix,
+}
+
+/// An ISO 4217 currency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Currency {
+    Euro,
+    PoundSterling,
+    UsDollar,
+    SwissFranc,
+    SwedishKrona,
+    DanishKrone,
+    NorwegianKrone,
+    PolishZloty,
+    CzechKoruna,
+    JapaneseYen,
+    /// Any other currency
+    Other {
+        /// The ISO 4217 alphabetic code, e.g. `HUF`
+        code: String,
+        /// The symbol printed next to amounts, e.g. `Ft`
+        symbol: String,
+        /// The amount of digits after the decimal separator
+        minor_units: u32,
+        /// Where the symbol is placed relative to an amount
+        placement: SymbolPlacement,
+    },
+}
+
+impl Currency {
+    /// Get a known currency by its ISO 4217 alphabetic code, e.g. `EUR`.
+    /// The code is matched case-insensitively.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let currency = match code.to_ascii_uppercase().as_str() {
+            "EUR" => Self::Euro,
+            "GBP" => Self::PoundSterling,
+            "USD" => Self::UsDollar,
+            "CHF" => Self::SwissFranc,
+            "SEK" => Self::SwedishKrona,
+            "DKK" => Self::DanishKrone,
+            "NOK" => Self::NorwegianKrone,
+            "PLN" => Self::PolishZloty,
+            "CZK" => Self::CzechKoruna,
+            "JPY" => Self::JapaneseYen,
+            _ => return None,
+        };
+
+        Some(currency)
+    }
+
+    /// The ISO 4217 alphabetic code
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Euro => "EUR",
+            Self::PoundSterling => "GBP",
+            Self::UsDollar => "USD",
+            Self::SwissFranc => "CHF",
+            Self::SwedishKrona => "SEK",
+            Self::DanishKrone => "DKK",
+            Self::NorwegianKrone => "NOK",
+            Self::PolishZloty => "PLN",
+            Self::CzechKoruna => "CZK",
+            Self::JapaneseYen => "JPY",
+            Self::Other { code, .. } => code,
+        }
+    }
+
+    /// The symbol printed next to amounts
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::Euro => "€",
+            Self::PoundSterling => "£",
+            Self::UsDollar => "$",
+            Self::SwissFranc => "CHF",
+            Self::SwedishKrona | Self::DanishKrone | Self::NorwegianKrone => "kr",
+            Self::PolishZloty => "zł",
+            Self::CzechKoruna => "Kč",
+            Self::JapaneseYen => "¥",
+            Self::Other { symbol, .. } => symbol,
+        }
+    }
+
+    /// The amount of digits after the decimal separator, as defined by ISO 4217
+    pub fn minor_units(&self) -> u32 {
+        match self {
+            Self::JapaneseYen => 0,
+            Self::Other { minor_units, .. } => *minor_units,
+            _ => 2,
+        }
+    }
+
+    /// Where the symbol is placed relative to an amount
+    pub fn placement(&self) -> SymbolPlacement {
+        match self {
+            Self::SwedishKrona | Self::DanishKrone | Self::PolishZloty | Self::CzechKoruna => {
+                SymbolPlacement::Suffix
+            }
+            Self::Other { placement, .. } => *placement,
+            _ => SymbolPlacement::Prefix,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn half_up_rounds_midpoints_away_from_zero() {
+        let round = |amount| Rounding::HalfUp.round(amount, 2);
+        assert_eq!(round(dec!(0.125)), dec!(0.13));
+        assert_eq!(round(dec!(0.135)), dec!(0.14));
+        assert_eq!(round(dec!(-0.125)), dec!(-0.13));
+        assert_eq!(round(dec!(0.1249)), dec!(0.12));
+        assert_eq!(round(dec!(-0.1251)), dec!(-0.13));
+    }
+
+    #[test]
+    fn half_even_rounds_midpoints_to_even() {
+        let round = |amount| Rounding::HalfEven.round(amount, 2);
+        assert_eq!(round(dec!(0.125)), dec!(0.12));
+        assert_eq!(round(dec!(0.135)), dec!(0.14));
+        assert_eq!(round(dec!(-0.125)), dec!(-0.12));
+        assert_eq!(round(dec!(-0.135)), dec!(-0.14));
+        assert_eq!(round(dec!(0.1251)), dec!(0.13));
+    }
+
+    #[test]
+    fn rounds_to_provided_decimals() {
+        assert_eq!(Rounding::HalfUp.round(dec!(2.5), 0), dec!(3));
+        assert_eq!(Rounding::HalfEven.round(dec!(2.5), 0), dec!(2));
+        assert_eq!(Rounding::HalfUp.round(dec!(1.0005), 3), dec!(1.001));
+        // Amounts with fewer decimals are left as they are
+        assert_eq!(Rounding::HalfUp.round(dec!(12.5), 2).to_string(), "12.5");
+    }
+
+    fn kuwaiti_dinar() -> Currency {
+        Currency::Other {
+            code: "KWD".to_string(),
+            symbol: "KD".to_string(),
+            minor_units: 3,
+            placement: SymbolPlacement::Prefix,
+        }
+    }
+
+    #[test]
+    fn finds_currencies_by_code() {
+        assert_eq!(Currency::from_code("EUR"), Some(Currency::Euro));
+        assert_eq!(Currency::from_code("jpy"), Some(Currency::JapaneseYen));
+        assert_eq!(Currency::from_code("KWD"), None);
+        for code in [
+            "EUR", "GBP", "USD", "CHF", "SEK", "DKK", "NOK", "PLN", "CZK", "JPY",
+        ] {
+            assert_eq!(Currency::from_code(code).unwrap().code(), code);
+        }
+    }
+
+    #[test]
+    fn describes_known_currencies() {
+        let euro = Currency::Euro;
+        assert_eq!(
+            (euro.symbol(), euro.minor_units(), euro.placement()),
+            ("€", 2, SymbolPlacement::Prefix)
+        );
+        let krona = Currency::SwedishKrona;
+        assert_eq!(
+            (krona.symbol(), krona.minor_units(), krona.placement()),
+            ("kr", 2, SymbolPlacement::Suffix)
+        );
+        let yen = Currency::JapaneseYen;
+        assert_eq!(
+            (yen.symbol(), yen.minor_units(), yen.placement()),
+            ("¥", 0, SymbolPlacement::Prefix)
+        );
+        assert_eq!(Currency::CzechKoruna.to_string(), "Kč");
+    }
+
+    #[test]
+    fn describes_other_currencies() {
+        let dinar = kuwaiti_dinar();
+        assert_eq!(dinar.code(), "KWD");
+        assert_eq!(dinar.symbol(), "KD");
+        assert_eq!(dinar.minor_units(), 3);
+        assert_eq!(dinar.placement(), SymbolPlacement::Prefix);
+        assert_eq!(dinar.to_string(), "KD");
+    }
+
+    #[test]
+    fn e_invoice_amounts_use_currency_decimals_up_to_two() {
+        let mut invoice = crate::invoice::tests::sample_invoice();
+
+        invoice.currency = Currency::JapaneseYen;
+        assert_eq!(invoice.e_invoice_amount(dec!(1234.5)), "1235");
+
+        // EN 16931 allows at most two decimals
+        invoice.currency = kuwaiti_dinar();
+        assert_eq!(invoice.e_invoice_amount(dec!(1.2345)), "1.23");
+
+        invoice.currency = Currency::Euro;
+        assert_eq!(invoice.e_invoice_amount(dec!(1.005)), "1.01");
+    }
+
+    #[test]
+    fn vat_is_rounded_to_currency_decimals() {
+        let mut invoice = crate::invoice::tests::sample_invoice();
+        invoice.currency = Currency::JapaneseYen;
+        let vat: Vec<_> = invoice
+            .vat_breakdown()
+            .into_iter()
+            .map(|summary| summary.vat_amount)
+            .collect();
+        // 21% of 119.90 and 9% of 9.95
+        assert_eq!(vat, [dec!(25), dec!(1)]);
+
+        invoice.currency = kuwaiti_dinar();
+        let vat: Vec<_> = invoice
+            .vat_breakdown()
+            .into_iter()
+            .map(|summary| summary.vat_amount)
+            .collect();
+        assert_eq!(vat, [dec!(25.179), dec!(0.896)]);
+    }
+}
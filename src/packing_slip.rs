@@ -1,12 +1,17 @@
+use crate::barcode::{code128, ean13, qr_code, Bitmap};
 use crate::footer::Footer;
 use crate::header::Header;
+use crate::layout::{BlockElementExt, ElementPropertyContainerExt, TableExt};
+use crate::pagination::{add_paginated_table, PaginatedTable};
+use crate::validate::{field_path, index_path, require_non_empty};
 use crate::RenderTarget;
-use crate::{Error, PdfRenderable};
+use crate::{DocumentMetadata, Error, Label, NaiveDate, PdfRenderable, Validate, ValidationIssue};
 use itext::itext::kernel::ColorConstant;
 use itext::itext::layout::{
-    BlockElement, Border, Cell, Document, ElementPropertyContainer, Paragraph, Table,
+    BlockElement, Border, Cell, ElementPropertyContainer, Image, Paragraph, Table, TextAlignment,
 };
 use jni::JNIEnv;
+use std::ops::Range;
 
 pub struct PackingSlip {
     /// Items to be included in the shipment
@@ -22,7 +27,19 @@ pub struct PackingSlip {
     /// The ID of this packing slip
     pub packing_slip_id: String,
     /// The expected delivery date
-    pub delivery_date: String,
+    pub delivery_date: NaiveDate,
+    /// Print the packing slip ID as barcode in the document information,
+    /// so the shipment can be confirmed by scanning the packing slip
+    pub packing_slip_id_barcode: Option<PackingSlipIdBarcode>,
+}
+
+/// The kind of barcode of the packing slip ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackingSlipIdBarcode {
+    /// Code 128, the packing slip ID must consist of printable ASCII characters
+    Code128,
+    /// QR code
+    QrCode,
 }
 
 /// Information about an article included in the shipment
@@ -37,59 +54,390 @@ pub struct ArticlePackingInfo {
     pub quantity_delivered: u32,
     /// The amount on backorder
     pub quantity_backorder: u32,
+    /// Barcode to scan the article with.
+    /// If any article has a barcode, the articles table gets a barcode column.
+    pub barcode: Option<ArticleBarcode>,
+}
+
+/// A barcode identifying an article
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArticleBarcode {
+    /// The article's GTIN-13 as EAN-13 barcode, including the check digit
+    Ean13(String),
+    /// Code 128 barcode of printable ASCII text, e.g. the article ID
+    Code128(String),
+}
+
+impl ArticleBarcode {
+    /// Encode the barcode, or None if its text cannot be encoded
+    fn encode(&self) -> Option<Bitmap> {
+        match self {
+            Self::Ean13(gtin) => ean13(gtin),
+            Self::Code128(text) => code128(text),
+        }
+    }
 }
 
+/// The width of a module of the linear barcodes, in points
+const BARCODE_MODULE_WIDTH: f32 = 0.9;
+
+/// The narrowest modules linear barcodes are scaled down to if they do not fit, in points (0.25 mm).
+/// Scanners cannot read narrower modules reliably, so barcodes which still do not fit are reported as validation issue.
+const MIN_BARCODE_MODULE_WIDTH: f32 = 0.72;
+
+/// The padding iText applies to every side of a table cell, in points
+const CELL_PADDING: f32 = 2.0;
+
+/// The relative widths of the columns of the articles table
+const ARTICLE_COLUMNS: [f32; 7] = [1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 1.0];
+
+/// The relative widths of the columns of the articles table if no article has a barcode
+const ARTICLE_COLUMNS_WITHOUT_BARCODE: [f32; 6] = [1.0, 1.0, 2.0, 1.0, 1.0, 1.0];
+
+/// The index of the barcode column in [ARTICLE_COLUMNS]
+const ARTICLE_BARCODE_COLUMN: usize = 2;
+
+/// The height of article barcodes, in points
+const ARTICLE_BARCODE_HEIGHT: f32 = 25.0;
+
+/// The amount of equally wide columns of the document information table
+const DOCUMENT_INFO_COLUMNS: usize = 5;
+
+/// The height of the Code 128 barcode of the packing slip ID, in points
+const PACKING_SLIP_ID_BARCODE_HEIGHT: f32 = 30.0;
+
+/// The size of the QR code of the packing slip ID, in points
+const PACKING_SLIP_ID_QR_CODE_SIZE: f32 = 60.0;
+
 impl PdfRenderable for PackingSlip {
     fn render<'a>(&self, target: &RenderTarget<'a>, env: &mut JNIEnv<'a>) -> Result<(), Error> {
-        self.header.render(target, env)?;
-        self.render_document_info(&target.document, env)?;
-        self.render_articles_section(&target.document, env)?;
-        self.footer.render(target, env)?;
+        self.render_sections(target, env)
+            .map_err(|e| e.resolve_java_exception(env))
+    }
+}
 
-        Ok(())
+impl Validate for PackingSlip {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        self.header.validate_at(&field_path(path, "header"), issues);
+        self.footer.validate_at(&field_path(path, "footer"), issues);
+
+        require_non_empty(&self.reference_id, path, "reference_id", issues);
+        require_non_empty(&self.order_id, path, "order_id", issues);
+        require_non_empty(&self.packing_slip_id, path, "packing_slip_id", issues);
+
+        if self.items.is_empty() {
+            issues.push(ValidationIssue {
+                path: field_path(path, "items"),
+                message: "must contain at least one item".to_string(),
+            });
+        }
+
+        let items_path = field_path(path, "items");
+        for (idx, item) in self.items.iter().enumerate() {
+            item.validate_at(&index_path(&items_path, idx), issues);
+        }
+
+        if let Err(issue) = self.encode_packing_slip_id_barcode(path) {
+            issues.push(issue);
+        }
+    }
+}
+
+impl Validate for ArticlePackingInfo {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        require_non_empty(&self.identifier, path, "identifier", issues);
+        require_non_empty(&self.description, path, "description", issues);
+
+        if let Err(issue) = self.encode_barcode(path) {
+            issues.push(issue);
+        }
+    }
+}
+
+impl ArticlePackingInfo {
+    /// Encode the article's barcode, if any.
+    /// `path` is the path of the article within the document.
+    ///
+    /// # Errors
+    ///
+    /// If the barcode cannot be encoded
+    fn encode_barcode(&self, path: &str) -> Result<Option<Bitmap>, ValidationIssue> {
+        let Some(barcode) = &self.barcode else {
+            return Ok(None);
+        };
+        let issue = |message: String| ValidationIssue {
+            path: field_path(path, "barcode"),
+            message,
+        };
+
+        let bitmap = barcode.encode().ok_or_else(|| {
+            let message = match barcode {
+                ArticleBarcode::Ean13(_) => "must be 13 digits with a valid check digit",
+                ArticleBarcode::Code128(_) => {
+                    "must be non-empty and consist of printable ASCII characters"
+                }
+            };
+            issue(message.to_string())
+        })?;
+        Ok(Some(bitmap))
+    }
+
+    /// Encode the article's barcode, if any, and size it to fit `available` points.
+    /// `path` is the path of the article within the document.
+    ///
+    /// # Errors
+    ///
+    /// If the barcode cannot be encoded or does not fit
+    fn size_barcode(
+        &self,
+        path: &str,
+        available: f32,
+    ) -> Result<Option<SizedBarcode>, ValidationIssue> {
+        let Some(bitmap) = self.encode_barcode(path)? else {
+            return Ok(None);
+        };
+        let width =
+            linear_barcode_width(&bitmap, available).map_err(|message| ValidationIssue {
+                path: field_path(path, "barcode"),
+                message,
+            })?;
+        Ok(Some(SizedBarcode { bitmap, width }))
+    }
+}
+
+/// A barcode and the width it is printed at, in points
+struct SizedBarcode {
+    bitmap: Bitmap,
+    width: f32,
+}
+
+/// The barcodes of a packing slip, encoded and sized before rendering
+struct EncodedBarcodes {
+    /// The barcode of the packing slip ID
+    packing_slip_id: Option<SizedBarcode>,
+    /// The barcode of every article, in the order of the items
+    articles: Vec<Option<SizedBarcode>>,
+}
+
+/// The width of a linear barcode in points, if it is printed in at most `available` points.
+/// Modules are [BARCODE_MODULE_WIDTH] wide, or narrower down to [MIN_BARCODE_MODULE_WIDTH] if the barcode does not fit otherwise.
+///
+/// # Errors
+///
+/// If the barcode does not fit, not even with the narrowest modules
+fn linear_barcode_width(bitmap: &Bitmap, available: f32) -> Result<f32, String> {
+    let modules = bitmap.width() as f32;
+    let module_width = BARCODE_MODULE_WIDTH.min(available / modules);
+    if module_width < MIN_BARCODE_MODULE_WIDTH {
+        return Err(format!(
+            "is too long for a barcode: it would be at least {:.1} points wide, at most {available:.1} fit",
+            modules * MIN_BARCODE_MODULE_WIDTH
+        ));
     }
+    Ok(modules * module_width)
 }
 
-const NUMBER_BACKORDER_LABEL: &str = "Aantal backorder";
-const NUMBER_DELIVERED_LABEL: &str = "Aantal geleverd";
-const NUMBER_ORDERED_LABEL: &str = "Aantal besteld";
-const ORDER_ID_LABEL: &str = "Order nummer";
-const ARTICLE_LABEL: &str = "Artikel";
-const DESCRIPTION_LABEL: &str = "Omschrijving";
-const PACKING_SLIP_ID_LABEL: &str = "Pakbonnummer";
-const OUR_REFERENCE_LABEL: &str = "Onze referentie";
-const CONCERNING_LABEL: &str = "Betreft";
-const ORDER_ID_PREFIX: &str = "Bestelling #:";
-const DELIVERY_DATE_LABEL: &str = "Afleverdatum";
+/// The width available to the content of a table cell, in points.
+/// The cell is in column `column` of a table with relative column widths `columns`,
+/// which takes up all of the `content_width` of the page.
+fn cell_content_width(columns: &[f32], column: usize, content_width: f32) -> f32 {
+    content_width * columns[column] / columns.iter().sum::<f32>() - 2.0 * CELL_PADDING
+}
 
 impl PackingSlip {
+    /// Render all sections of the packing slip.
+    ///
+    /// # Errors
+    ///
+    /// If a barcode cannot be encoded, or if a JNI error occurs
+    fn render_sections<'a>(
+        &self,
+        target: &RenderTarget<'a>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let barcodes = self.encode_barcodes(target.content_width(env)?)?;
+        target.apply_metadata(&self.metadata(), env)?;
+        target.set_footer(&self.footer, env)?;
+        self.header.render(target, env)?;
+        self.render_document_info(target, barcodes.packing_slip_id.as_ref(), env)?;
+        self.render_articles_section(target, &barcodes.articles, env)?;
+        self.header.render_logo_strip(target, env)?;
+
+        Ok(())
+    }
+
+    /// The metadata describing this packing slip, e.g. its title and seller
+    fn metadata(&self) -> DocumentMetadata {
+        DocumentMetadata {
+            title: Some(format!("{} {}", self.header.label, self.packing_slip_id)),
+            author: Some(self.header.seller.name.clone()),
+            keywords: [&self.packing_slip_id, &self.order_id, &self.reference_id]
+                .into_iter()
+                .filter(|keyword| !keyword.is_empty())
+                .cloned()
+                .collect(),
+            ..DocumentMetadata::default()
+        }
+    }
+
+    /// Encode the barcode of the packing slip ID, if any.
+    /// `path` is the path of the packing slip within the document.
+    ///
+    /// # Errors
+    ///
+    /// If the packing slip ID cannot be encoded
+    fn encode_packing_slip_id_barcode(
+        &self,
+        path: &str,
+    ) -> Result<Option<Bitmap>, ValidationIssue> {
+        let issue = |message: &str| ValidationIssue {
+            path: field_path(path, "packing_slip_id"),
+            message: message.to_string(),
+        };
+
+        let bitmap = match self.packing_slip_id_barcode {
+            Some(PackingSlipIdBarcode::Code128) => {
+                code128(&self.packing_slip_id).ok_or_else(|| {
+                    issue("must consist of printable ASCII characters for Code 128 barcodes")
+                })?
+            }
+            Some(PackingSlipIdBarcode::QrCode) => qr_code(self.packing_slip_id.as_bytes())
+                .ok_or_else(|| issue("is too long for a QR code"))?,
+            None => return Ok(None),
+        };
+        Ok(Some(bitmap))
+    }
+
+    /// Encode the barcode of the packing slip ID, if any, and size it to fit `available` points.
+    /// QR codes are always printed at the same size.
+    /// `path` is the path of the packing slip within the document.
+    ///
+    /// # Errors
+    ///
+    /// If the packing slip ID cannot be encoded or its barcode does not fit
+    fn size_packing_slip_id_barcode(
+        &self,
+        path: &str,
+        available: f32,
+    ) -> Result<Option<SizedBarcode>, ValidationIssue> {
+        let Some(bitmap) = self.encode_packing_slip_id_barcode(path)? else {
+            return Ok(None);
+        };
+        let width = match self.packing_slip_id_barcode {
+            Some(PackingSlipIdBarcode::QrCode) => PACKING_SLIP_ID_QR_CODE_SIZE,
+            _ => linear_barcode_width(&bitmap, available).map_err(|message| ValidationIssue {
+                path: field_path(path, "packing_slip_id"),
+                message,
+            })?,
+        };
+        Ok(Some(SizedBarcode { bitmap, width }))
+    }
+
+    /// Encode all barcodes on the packing slip and size them to fit their cells,
+    /// if the content of the pages is `content_width` points wide.
+    ///
+    /// # Errors
+    ///
+    /// If any barcode cannot be encoded or does not fit
+    fn encode_barcodes(&self, content_width: f32) -> Result<EncodedBarcodes, Error> {
+        let mut issues = Vec::new();
+        let article_width =
+            cell_content_width(&ARTICLE_COLUMNS, ARTICLE_BARCODE_COLUMN, content_width);
+        let articles = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                item.size_barcode(&index_path("items", idx), article_width)
+                    .unwrap_or_else(|issue| {
+                        issues.push(issue);
+                        None
+                    })
+            })
+            .collect();
+
+        // The barcode is printed in the last column, above the packing slip ID
+        let columns = [1.0; DOCUMENT_INFO_COLUMNS];
+        let packing_slip_id_width =
+            cell_content_width(&columns, DOCUMENT_INFO_COLUMNS - 1, content_width);
+        let packing_slip_id = self
+            .size_packing_slip_id_barcode("", packing_slip_id_width)
+            .unwrap_or_else(|issue| {
+                issues.push(issue);
+                None
+            });
+
+        if issues.is_empty() {
+            Ok(EncodedBarcodes {
+                packing_slip_id,
+                articles,
+            })
+        } else {
+            Err(Error::Validation(issues))
+        }
+    }
+
+    /// Create the image of the packing slip ID's barcode
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn packing_slip_id_barcode<'a>(
+        &self,
+        barcode: &SizedBarcode,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<Image<'a>, Error> {
+        let height = match self.packing_slip_id_barcode {
+            Some(PackingSlipIdBarcode::QrCode) => barcode.width,
+            _ => PACKING_SLIP_ID_BARCODE_HEIGHT,
+        };
+        barcode.bitmap.to_image(barcode.width, height, env)
+    }
+
+    /// Whether the articles table has a barcode column
+    fn has_barcode_column(&self) -> bool {
+        self.items.iter().any(|item| item.barcode.is_some())
+    }
+
     /// Render the packing slip information.
     /// This includes:
     /// - Our reference
     /// - Delivery date
     /// - Order ID
-    /// - Packing slip ID
+    /// - Packing slip ID, optionally as barcode
     ///
     /// # Errors
     ///
     /// If a JNI error occurs
     fn render_document_info<'a>(
         &self,
-        doc: &Document<'a>,
+        target: &RenderTarget<'a>,
+        packing_slip_id_barcode: Option<&SizedBarcode>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
-        let document_info = Table::new(&[1.0, 1.0, 1.0, 1.0, 1.0], env)?;
+        let doc = &target.document;
+        let translations = &target.translations;
+        let document_info = Table::new(&[1.0; DOCUMENT_INFO_COLUMNS], env)?;
         let black_border = Border::Solid {
             color: ColorConstant::Black,
             width: 1.0,
         };
+        // The barcode of the packing slip ID is printed above the packing slip ID
+        let barcode = Cell::new(env)?;
+        barcode.set_border(Border::NoBorder, env)?;
+        if let Some(sized) = packing_slip_id_barcode {
+            barcode.add_image(&self.packing_slip_id_barcode(sized, env)?, env)?;
+        }
         document_info
             .use_all_available_width(env)?
             .start_new_row(env)?
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    Paragraph::new_with_text(&format!("{CONCERNING_LABEL}: "), env)?
-                        .set_bold(env)?,
+                    Paragraph::new_with_text(
+                        &format!("{}: ", translations.get(Label::Concerning)),
+                        env,
+                    )?
+                    .set_bold_face(target.faces, env)?,
                     env,
                 )?,
                 env,
@@ -97,7 +445,11 @@ impl PackingSlip {
             .add_cell(
                 Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
                     &Paragraph::new_with_text(
-                        &format!("{ORDER_ID_PREFIX} {}", self.reference_id),
+                        &format!(
+                            "{} {}",
+                            translations.get(Label::OrderIdPrefix),
+                            self.reference_id
+                        ),
                         env,
                     )?,
                     env,
@@ -106,14 +458,15 @@ impl PackingSlip {
             )?
             .add_cell(Cell::new(env)?.set_border(Border::NoBorder, env)?, env)?
             .add_cell(Cell::new(env)?.set_border(Border::NoBorder, env)?, env)?
-            .add_cell(Cell::new(env)?.set_border(Border::NoBorder, env)?, env)?
+            .add_cell(&barcode, env)?
             .start_new_row(env)?
             .add_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(OUR_REFERENCE_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::OurReference), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -129,7 +482,8 @@ impl PackingSlip {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(black_border.clone(), env)?
                     .add(
-                        &Paragraph::new_with_text(DELIVERY_DATE_LABEL, env)?.set_bold(env)?,
+                        &Paragraph::new_with_text(translations.get(Label::DeliveryDate), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -139,7 +493,8 @@ impl PackingSlip {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(black_border.clone(), env)?
                     .add(
-                        &Paragraph::new_with_text(ORDER_ID_LABEL, env)?.set_bold(env)?,
+                        &Paragraph::new_with_text(translations.get(Label::OrderId), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -149,7 +504,8 @@ impl PackingSlip {
                     .set_border(Border::NoBorder, env)?
                     .set_border_top(black_border.clone(), env)?
                     .add(
-                        &Paragraph::new_with_text(PACKING_SLIP_ID_LABEL, env)?.set_bold(env)?,
+                        &Paragraph::new_with_text(translations.get(Label::PackingSlipId), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -161,7 +517,11 @@ impl PackingSlip {
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
                         &Paragraph::new_with_text(
-                            &format!("{ORDER_ID_PREFIX} : {}", self.reference_id),
+                            &format!(
+                                "{} : {}",
+                                translations.get(Label::OrderIdPrefix),
+                                self.reference_id
+                            ),
                             env,
                         )?,
                         env,
@@ -178,7 +538,13 @@ impl PackingSlip {
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
-                    .add(&Paragraph::new_with_text(&self.delivery_date, env)?, env)?,
+                    .add(
+                        &Paragraph::new_with_text(
+                            &translations.locale.format_date(self.delivery_date),
+                            env,
+                        )?,
+                        env,
+                    )?,
                 env,
             )?
             .add_cell(
@@ -233,32 +599,28 @@ impl PackingSlip {
         Ok(())
     }
 
-    /// Render the article section of the packing slip
+    /// Render the article section of the packing slip.
+    /// `barcodes` are the encoded barcodes of the articles, in the order of the items.
     ///
     /// # Errors
     ///
     /// If a JNI error occurs
     fn render_articles_section<'a>(
         &self,
-        doc: &Document<'a>,
+        target: &RenderTarget<'a>,
+        barcodes: &[Option<SizedBarcode>],
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
-        let articles = Table::new(&[1.0, 1.0, 2.0, 1.0, 1.0, 1.0], env)?;
-        articles.use_all_available_width(env)?;
-
-        self.render_articles_header(&articles, env)?;
-
-        for article in &self.items {
-            self.render_article(article, &articles, env)?;
-        }
-
-        doc.add(articles, env)?;
-
-        Ok(())
+        let articles = ArticlesTable {
+            packing_slip: self,
+            target,
+            barcodes,
+        };
+        add_paginated_table(&articles, &target.document, env)
     }
 
     /// Render the header of the articles section.
-    /// I.e. the table header
+    /// I.e. the table header, which is repeated on every page
     ///
     /// # Errors
     ///
@@ -266,71 +628,92 @@ impl PackingSlip {
     fn render_articles_header<'a>(
         &self,
         table: &Table<'a>,
+        target: &RenderTarget<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
+        let translations = &target.translations;
         let black_border = Border::Solid {
             width: 1.0,
             color: ColorConstant::Black,
         };
 
         table
-            .start_new_row(env)?
-            .add_cell(
+            .add_header_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(ORDER_ID_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::OrderId), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
             )?
-            .add_cell(
+            .add_header_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(ARTICLE_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::Article), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
-            )?
-            .add_cell(
+            )?;
+        if self.has_barcode_column() {
+            table.add_header_cell(
+                Cell::new(env)?
+                    .set_border(Border::NoBorder, env)?
+                    .set_border_bottom(black_border.clone(), env)?
+                    .add(
+                        Paragraph::new_with_text(translations.get(Label::Barcode), env)?
+                            .set_bold_face(target.faces, env)?,
+                        env,
+                    )?,
+                env,
+            )?;
+        }
+        table
+            .add_header_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(DESCRIPTION_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::Description), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
             )?
-            .add_cell(
+            .add_header_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(NUMBER_DELIVERED_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::QuantityDelivered), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
             )?
-            .add_cell(
+            .add_header_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(NUMBER_ORDERED_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::QuantityOrdered), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
             )?
-            .add_cell(
+            .add_header_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
                     .set_border_bottom(black_border.clone(), env)?
                     .add(
-                        Paragraph::new_with_text(NUMBER_BACKORDER_LABEL, env)?.set_bold(env)?,
+                        Paragraph::new_with_text(translations.get(Label::QuantityBackorder), env)?
+                            .set_bold_face(target.faces, env)?,
                         env,
                     )?,
                 env,
@@ -339,7 +722,7 @@ impl PackingSlip {
         Ok(())
     }
 
-    /// Render a single article to the table.
+    /// Render a single article to the table, with its encoded barcode if it has one.
     ///
     /// # Errors
     ///
@@ -347,6 +730,7 @@ impl PackingSlip {
     fn render_article<'a>(
         &self,
         article: &ArticlePackingInfo,
+        barcode: Option<&SizedBarcode>,
         table: &Table<'a>,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
@@ -355,43 +739,293 @@ impl PackingSlip {
             .add_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
+                    .set_keep_together(true, env)?
                     .add(&Paragraph::new_with_text(&self.order_id, env)?, env)?,
                 env,
             )?
             .add_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
+                    .set_keep_together(true, env)?
                     .add(&Paragraph::new_with_text(&article.identifier, env)?, env)?,
                 env,
-            )?
+            )?;
+        if self.has_barcode_column() {
+            let cell = Cell::new(env)?;
+            cell.set_border(Border::NoBorder, env)?
+                .set_keep_together(true, env)?;
+            if let Some(barcode) = barcode {
+                let image = barcode
+                    .bitmap
+                    .to_image(barcode.width, ARTICLE_BARCODE_HEIGHT, env)?;
+                cell.add_image(&image, env)?;
+            }
+            table.add_cell(&cell, env)?;
+        }
+        table
             .add_cell(
                 Cell::new(env)?
                     .set_border(Border::NoBorder, env)?
+                    .set_keep_together(true, env)?
                     .add(&Paragraph::new_with_text(&article.description, env)?, env)?,
                 env,
             )?
             .add_cell(
-                Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(&article.quantity_delivered.to_string(), env)?,
-                    env,
-                )?,
+                Cell::new(env)?
+                    .set_border(Border::NoBorder, env)?
+                    .set_keep_together(true, env)?
+                    .add(
+                        &Paragraph::new_with_text(&article.quantity_delivered.to_string(), env)?,
+                        env,
+                    )?,
                 env,
             )?
             .add_cell(
-                Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(&article.quantity_ordered.to_string(), env)?,
-                    env,
-                )?,
+                Cell::new(env)?
+                    .set_border(Border::NoBorder, env)?
+                    .set_keep_together(true, env)?
+                    .add(
+                        &Paragraph::new_with_text(&article.quantity_ordered.to_string(), env)?,
+                        env,
+                    )?,
                 env,
             )?
             .add_cell(
-                Cell::new(env)?.set_border(Border::NoBorder, env)?.add(
-                    &Paragraph::new_with_text(&article.quantity_backorder.to_string(), env)?,
-                    env,
-                )?,
+                Cell::new(env)?
+                    .set_border(Border::NoBorder, env)?
+                    .set_keep_together(true, env)?
+                    .add(
+                        &Paragraph::new_with_text(&article.quantity_backorder.to_string(), env)?,
+                        env,
+                    )?,
                 env,
             )?;
 
         Ok(())
     }
 }
+
+/// The articles of a packing slip, spread over pages
+struct ArticlesTable<'r, 'a> {
+    packing_slip: &'r PackingSlip,
+    target: &'r RenderTarget<'a>,
+    /// The encoded barcodes of the articles, in the order of the items
+    barcodes: &'r [Option<SizedBarcode>],
+}
+
+impl<'a> PaginatedTable<'a> for ArticlesTable<'_, 'a> {
+    fn columns(&self) -> Vec<f32> {
+        if self.packing_slip.has_barcode_column() {
+            ARTICLE_COLUMNS.to_vec()
+        } else {
+            ARTICLE_COLUMNS_WITHOUT_BARCODE.to_vec()
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        self.packing_slip.items.len()
+    }
+
+    fn render_header(&self, table: &Table<'a>, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        self.packing_slip
+            .render_articles_header(table, self.target, env)
+    }
+
+    fn render_row(&self, table: &Table<'a>, idx: usize, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        let article = &self.packing_slip.items[idx];
+        let barcode = self.barcodes[idx].as_ref();
+        self.packing_slip
+            .render_article(article, barcode, table, env)
+    }
+
+    fn render_brought_forward(
+        &self,
+        _table: &Table<'a>,
+        _printed: Range<usize>,
+        _env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        // Nothing is carried over, the repeated header suffices
+        Ok(())
+    }
+
+    fn render_carried_forward(
+        &self,
+        table: &Table<'a>,
+        _printed: Range<usize>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let translations = &self.target.translations;
+        table.start_new_row(env)?.add_cell(
+            Cell::new_with_span(1, self.columns().len() as i32, env)?
+                .set_border(Border::NoBorder, env)?
+                .add(
+                    Paragraph::new_with_text(translations.get(Label::ContinuedOnNextPage), env)?
+                        .set_italic_face(self.target.faces, env)?
+                        .set_text_alignment(TextAlignment::Right, env)?,
+                    env,
+                )?,
+            env,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invoice::tests::sample_invoice;
+
+    fn article(barcode: Option<ArticleBarcode>) -> ArticlePackingInfo {
+        ArticlePackingInfo {
+            identifier: "15001A".to_string(),
+            description: "Filter falcon".to_string(),
+            quantity_ordered: 1,
+            quantity_delivered: 1,
+            quantity_backorder: 0,
+            barcode,
+        }
+    }
+
+    fn packing_slip(items: Vec<ArticlePackingInfo>) -> PackingSlip {
+        let invoice = sample_invoice();
+        PackingSlip {
+            items,
+            header: invoice.header,
+            footer: invoice.footer,
+            reference_id: "10273".to_string(),
+            order_id: "23138".to_string(),
+            packing_slip_id: "880".to_string(),
+            delivery_date: NaiveDate::from_ymd_opt(2023, 2, 7).unwrap(),
+            packing_slip_id_barcode: Some(PackingSlipIdBarcode::Code128),
+        }
+    }
+
+    /// The width of the content of A4 pages with the default margins
+    const A4_CONTENT_WIDTH: f32 = 595.0 - 30.0 - 30.0;
+
+    #[test]
+    fn encodes_each_barcode() {
+        let slip = packing_slip(vec![
+            article(Some(ArticleBarcode::Ean13("4006381333931".to_string()))),
+            article(None),
+            article(Some(ArticleBarcode::Code128("15001A".to_string()))),
+        ]);
+        let Ok(barcodes) = slip.encode_barcodes(A4_CONTENT_WIDTH) else {
+            panic!("barcodes should be valid");
+        };
+
+        let widths: Vec<_> = barcodes
+            .articles
+            .iter()
+            .map(|barcode| barcode.as_ref().map(|barcode| barcode.bitmap.width()))
+            .collect();
+        assert_eq!(widths, [Some(113), None, Some(110)]);
+        assert!(barcodes.packing_slip_id.is_some());
+    }
+
+    #[test]
+    fn scales_barcodes_down_to_fit() {
+        let mut slip = packing_slip(vec![article(Some(ArticleBarcode::Code128(
+            "15001AB".to_string(),
+        )))]);
+        slip.packing_slip_id = "PS-000880".to_string();
+        let Ok(barcodes) = slip.encode_barcodes(A4_CONTENT_WIDTH) else {
+            panic!("barcodes should fit");
+        };
+
+        // 121 modules fit in the barcode column, which is 535 * 2 / 9 - 4 = 114.9 points wide
+        let article = barcodes.articles[0].as_ref().unwrap();
+        assert_eq!(article.width, 121.0 * BARCODE_MODULE_WIDTH);
+        // 132 modules do not fit in the packing slip ID cell, which is 535 / 5 - 4 = 103 points wide
+        let packing_slip_id = barcodes.packing_slip_id.unwrap();
+        assert_eq!(packing_slip_id.bitmap.width(), 132);
+        assert!((packing_slip_id.width - 103.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn reports_barcodes_which_do_not_fit() {
+        let mut slip = packing_slip(vec![
+            article(Some(ArticleBarcode::Code128("15001A".to_string()))),
+            article(Some(ArticleBarcode::Code128("ABCDEFGHIJ".to_string()))),
+            article(Some(ArticleBarcode::Ean13("4006381333932".to_string()))),
+        ]);
+        slip.packing_slip_id = "PACKING-SLIP".to_string();
+
+        let Err(Error::Validation(issues)) = slip.encode_barcodes(A4_CONTENT_WIDTH) else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(
+            issues,
+            [
+                ValidationIssue {
+                    path: "items[1].barcode".to_string(),
+                    message: "is too long for a barcode: it would be at least 118.8 points wide, \
+                              at most 114.9 fit"
+                        .to_string(),
+                },
+                ValidationIssue {
+                    path: "items[2].barcode".to_string(),
+                    message: "must be 13 digits with a valid check digit".to_string(),
+                },
+                ValidationIssue {
+                    path: "packing_slip_id".to_string(),
+                    message: "is too long for a barcode: it would be at least 134.6 points wide, \
+                              at most 103.0 fit"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn barcode_limits_depend_on_page_width() {
+        let slip = packing_slip(vec![article(Some(ArticleBarcode::Code128(
+            "15001AB".to_string(),
+        )))]);
+        // Fits on A4, but not on A5 with the default margins
+        assert!(slip.encode_barcodes(A4_CONTENT_WIDTH).is_ok());
+        let Err(Error::Validation(issues)) = slip.encode_barcodes(420.0 - 30.0 - 30.0) else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(issues[0].path, "items[0].barcode");
+    }
+
+    #[test]
+    fn reports_paths_of_invalid_fields() {
+        let mut slip = packing_slip(vec![article(None), article(None)]);
+        slip.header.addressed_to.name = String::new();
+        slip.reference_id = " ".to_string();
+        slip.items[1].identifier = String::new();
+        slip.items[1].barcode = Some(ArticleBarcode::Code128(String::new()));
+
+        let paths: Vec<_> = slip
+            .validate()
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "header.addressed_to.name",
+                "reference_id",
+                "items[1].identifier",
+                "items[1].barcode",
+            ]
+        );
+
+        slip.items.clear();
+        assert!(slip
+            .validate()
+            .iter()
+            .any(|issue| issue.to_string() == "items: must contain at least one item"));
+    }
+
+    #[test]
+    fn qr_codes_are_not_limited_in_width() {
+        let mut slip = packing_slip(vec![article(None)]);
+        slip.packing_slip_id = "PS-000880".to_string();
+        slip.packing_slip_id_barcode = Some(PackingSlipIdBarcode::QrCode);
+        assert!(slip.encode_barcodes(A4_CONTENT_WIDTH).is_ok());
+    }
+}
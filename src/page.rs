@@ -0,0 +1,62 @@
+/// The size of the pages of a document
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+    /// A custom size in points, as the width and height of a portrait page.
+    /// The size is used as given, so a width larger than the height results in a wide page
+    /// unless the orientation is landscape, which swaps the width and height.
+    Custom {
+        width: f32,
+        height: f32,
+    },
+}
+
+impl PaperSize {
+    /// The width and height in points when the page has the provided orientation
+    pub fn dimensions(self, orientation: Orientation) -> (f32, f32) {
+        let (width, height) = match self {
+            Self::A4 => (595.0, 842.0),
+            Self::A5 => (420.0, 595.0),
+            Self::Letter => (612.0, 792.0),
+            Self::Legal => (612.0, 1008.0),
+            Self::Custom { width, height } => (width, height),
+        };
+
+        match orientation {
+            Orientation::Portrait => (width, height),
+            Orientation::Landscape => (height, width),
+        }
+    }
+}
+
+/// The orientation of the pages of a document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+/// The space between the content and the edges of a page, in points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Margins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Self {
+            top: 40.0,
+            right: 30.0,
+            bottom: 40.0,
+            left: 30.0,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{Label, Translations};
+
+/// Where page numbers are printed on the page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PageNumberPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomCenter,
+    BottomRight,
+}
+
+/// Settings for numbering the pages of a document, e.g. 'Page 1 of 3'.
+/// Page numbers are printed after all content has been rendered, when the total amount of pages is known.
+#[derive(Debug, Clone)]
+pub struct PageNumbering {
+    /// Where the page numbers are printed
+    pub position: PageNumberPosition,
+    /// The text printed on every page.
+    /// `{page}` is replaced by the page number and `{pages}` by the total amount of pages.
+    /// If left to None, the translation of [Label::PageNumber] is used.
+    pub format: Option<String>,
+    /// The font size of the page numbers
+    pub font_size: f32,
+}
+
+impl Default for PageNumbering {
+    fn default() -> Self {
+        Self {
+            position: PageNumberPosition::default(),
+            format: None,
+            font_size: 8.0,
+        }
+    }
+}
+
+impl PageNumbering {
+    /// The text printed on a page
+    ///
+    /// ```
+    /// # use order_pdf_printer::{Locale, PageNumbering, Translations};
+    /// let numbering = PageNumbering::default();
+    /// let translations = Translations::new(Locale::Dutch);
+    /// assert_eq!(numbering.text(1, 3, &translations), "Pagina 1 van 3");
+    /// ```
+    pub fn text(&self, page: i32, pages: i32, translations: &Translations) -> String {
+        let format = self
+            .format
+            .as_deref()
+            .unwrap_or_else(|| translations.get(Label::PageNumber));
+
+        format
+            .replace("{page}", &page.to_string())
+            .replace("{pages}", &pages.to_string())
+    }
+}
@@ -0,0 +1,245 @@
+//! Spreading the rows of a table over multiple pages.
+
+use crate::layout::DocumentExt;
+use crate::Error;
+use itext::itext::layout::{Document, Table};
+use jni::JNIEnv;
+use std::ops::Range;
+
+/// Room left unused at the bottom of every page when spreading the rows of a table over pages.
+/// The rows are measured separately, so the combined table may be slightly higher due to
+/// collapsed borders and rounding, which must not push its last row to a page of its own.
+const PAGINATION_MARGIN: f32 = 2.0;
+
+/// Heights used to spread the rows of a table over multiple pages
+pub(crate) struct PageLayout {
+    /// The height available on the page the table starts on
+    pub first_page: f32,
+    /// The height available on every following page
+    pub next_pages: f32,
+    /// The height of the table header, repeated on every page
+    pub header: f32,
+    /// The height of the rows closing a page the table continues after,
+    /// and of the rows opening the page it continues on
+    pub carry: f32,
+}
+
+impl PageLayout {
+    /// Split rows with the provided heights into the ranges of rows printed per page.
+    /// Every page except the last reserves room for the carry rows, as does every page except the first.
+    /// A row higher than a page gets a page of its own.
+    ///
+    /// If not even the first row fits on the first page, the first range is empty
+    /// and the table should start on the next page.
+    pub fn paginate(&self, row_heights: &[f32]) -> Vec<Range<usize>> {
+        let mut pages = Vec::new();
+        let mut start = 0;
+        let mut available = self.first_page - PAGINATION_MARGIN;
+        let mut used = self.header;
+
+        for (idx, height) in row_heights.iter().enumerate() {
+            let remaining: f32 = row_heights[idx..].iter().sum();
+            let fits_as_last = used + remaining <= available;
+            let fits = used + height + self.carry <= available;
+
+            let start_on_next_page = idx == 0 && self.first_page < self.next_pages;
+            if !fits && !fits_as_last && (idx > start || start_on_next_page) {
+                pages.push(start..idx);
+                start = idx;
+                available = self.next_pages - PAGINATION_MARGIN;
+                // Nothing is carried to the next page if the table did not start yet
+                used = if idx == 0 {
+                    self.header
+                } else {
+                    self.header + self.carry
+                };
+            }
+
+            used += height;
+        }
+
+        pages.push(start..row_heights.len());
+        pages
+    }
+}
+
+/// A table whose rows are spread over pages by [add_paginated_table].
+///
+/// iText repeats header and footer cells of a table on every page, but their content is fixed
+/// when the table is built, so they cannot show e.g. the subtotal of the rows before a page break.
+/// That would require a custom `TableRenderer`, a Java class which cannot be defined through JNI.
+/// Instead, the rows are measured up front and every page gets a table of its own,
+/// opened and closed by rows rendered for that specific break.
+pub(crate) trait PaginatedTable<'a> {
+    /// The relative widths of the columns
+    fn columns(&self) -> Vec<f32>;
+
+    /// The number of rows, excluding the header and carry rows
+    fn row_count(&self) -> usize;
+
+    /// Add the header, repeated on every page, to a table
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_header(&self, table: &Table<'a>, env: &mut JNIEnv<'a>) -> Result<(), Error>;
+
+    /// Start a new row in the table and add the row with index `idx` to it
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_row(&self, table: &Table<'a>, idx: usize, env: &mut JNIEnv<'a>) -> Result<(), Error>;
+
+    /// Add the rows opening a page the table continues on.
+    /// `printed` are the rows printed on the previous pages.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_brought_forward(
+        &self,
+        table: &Table<'a>,
+        printed: Range<usize>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error>;
+
+    /// Add the rows closing a page the table continues after.
+    /// `printed` are the rows printed up to and including this page.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn render_carried_forward(
+        &self,
+        table: &Table<'a>,
+        printed: Range<usize>,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error>;
+}
+
+/// Add a table to the document, spreading its rows over as many pages as needed.
+/// See [PaginatedTable] for why iText's own table splitting is not used.
+///
+/// # Errors
+///
+/// If a JNI error occurs
+pub(crate) fn add_paginated_table<'a, T: PaginatedTable<'a>>(
+    paginated: &T,
+    document: &Document<'a>,
+    env: &mut JNIEnv<'a>,
+) -> Result<(), Error> {
+    let columns = paginated.columns();
+    let new_table = |env: &mut JNIEnv<'a>| -> Result<Table<'a>, Error> {
+        let table = Table::new(&columns, env)?;
+        table.use_all_available_width(env)?;
+        Ok(table)
+    };
+
+    let page_size = document.get_pdf_document(env)?.get_default_page_size(env)?;
+    let width = page_size.get_width(env)?
+        - document.get_left_margin(env)?
+        - document.get_right_margin(env)?;
+
+    let header = new_table(env)?;
+    paginated.render_header(&header, env)?;
+    let carry = new_table(env)?;
+    paginated.render_carried_forward(&carry, 0..0, env)?;
+
+    let mut row_heights = Vec::with_capacity(paginated.row_count());
+    for idx in 0..paginated.row_count() {
+        let row = new_table(env)?;
+        paginated.render_row(&row, idx, env)?;
+        row_heights.push(document.measure_height(&row, width, env)?);
+    }
+
+    let layout = PageLayout {
+        first_page: document.get_remaining_height(env)?,
+        next_pages: page_size.get_height(env)?
+            - document.get_top_margin(env)?
+            - document.get_bottom_margin(env)?,
+        header: document.measure_height(&header, width, env)?,
+        carry: document.measure_height(&carry, width, env)?,
+    };
+    let pages = layout.paginate(&row_heights);
+
+    for (page_idx, rows) in pages.iter().enumerate() {
+        if page_idx > 0 {
+            document.add_area_break(env)?;
+        }
+
+        // The table starts on the next page
+        if rows.is_empty() && pages.len() > 1 {
+            continue;
+        }
+
+        let table = new_table(env)?;
+        paginated.render_header(&table, env)?;
+
+        if rows.start > 0 {
+            paginated.render_brought_forward(&table, 0..rows.start, env)?;
+        }
+
+        for idx in rows.clone() {
+            paginated.render_row(&table, idx, env)?;
+        }
+
+        if rows.end < row_heights.len() {
+            paginated.render_carried_forward(&table, 0..rows.end, env)?;
+        }
+
+        document.add(table, env)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: PageLayout = PageLayout {
+        first_page: 200.0,
+        next_pages: 300.0,
+        header: 20.0,
+        carry: 30.0,
+    };
+
+    #[test]
+    fn everything_fits_on_the_first_page() {
+        assert_eq!(LAYOUT.paginate(&[40.0; 4]), vec![0..4]);
+    }
+
+    #[test]
+    fn table_starts_on_the_next_page_if_the_first_row_does_not_fit() {
+        let layout = PageLayout {
+            first_page: 50.0,
+            ..LAYOUT
+        };
+        assert_eq!(layout.paginate(&[40.0, 40.0]), vec![0..0, 0..2]);
+    }
+
+    #[test]
+    fn row_filling_the_page_exactly_is_moved_to_the_next_page() {
+        // Without the pagination margin the row would fit exactly
+        let layout = PageLayout {
+            first_page: 100.0,
+            ..LAYOUT
+        };
+        assert_eq!(layout.paginate(&[80.0]), vec![0..0, 0..1]);
+    }
+
+    #[test]
+    fn row_higher_than_a_page_gets_a_page_of_its_own() {
+        assert_eq!(
+            LAYOUT.paginate(&[40.0, 400.0, 40.0]),
+            vec![0..1, 1..2, 2..3]
+        );
+    }
+
+    #[test]
+    fn pages_reserve_room_for_the_carry_rows() {
+        // The first page fits 20 + 3 * 50 = 170 of rows, but not the carry rows after them
+        assert_eq!(LAYOUT.paginate(&[50.0; 7]), vec![0..2, 2..6, 6..7]);
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// The PDF/A conformance levels documents can be archived in.
+/// Both levels only guarantee the visual appearance of the document can be reproduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfAConformanceLevel {
+    /// PDF/A-2b
+    PdfA2B,
+    /// PDF/A-3b, which also allows files of any type to be embedded, e.g. e-invoice XML
+    PdfA3B,
+}
+
+impl PdfAConformanceLevel {
+    /// The part of the PDF/A standard, as written in the XMP metadata
+    pub(crate) fn part(self) -> &'static str {
+        match self {
+            Self::PdfA2B => "2",
+            Self::PdfA3B => "3",
+        }
+    }
+
+    /// The conformance within the part, as written in the XMP metadata
+    pub(crate) fn conformance(self) -> &'static str {
+        match self {
+            Self::PdfA2B | Self::PdfA3B => "B",
+        }
+    }
+
+    /// The name of the constant in `com.itextpdf.kernel.pdf.PdfAConformanceLevel`
+    pub(crate) fn java_constant(self) -> &'static str {
+        match self {
+            Self::PdfA2B => "PDF_A_2B",
+            Self::PdfA3B => "PDF_A_3B",
+        }
+    }
+}
+
+impl fmt::Display for PdfAConformanceLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "PDF/A-{}{}",
+            self.part(),
+            self.conformance().to_lowercase()
+        )
+    }
+}
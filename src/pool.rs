@@ -0,0 +1,216 @@
+use crate::{DocumentConfiguration, Error, PdfRenderable, RenderTarget, JVM};
+use jni::JNIEnv;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::thread;
+
+/// A unit of work executed on a JVM-attached worker thread
+pub(crate) type Job = Box<dyn FnOnce(&mut JNIEnv<'_>) + Send>;
+
+/// The number of local references to reserve for every rendering job.
+/// The frame is released after the job finishes, regardless of this value.
+const LOCAL_FRAME_CAPACITY: i32 = 256;
+
+/// A process-wide handle for rendering documents.
+///
+/// The JVM is started lazily on the first render, after which a bounded
+/// amount of worker threads is spawned. Every worker stays attached to the JVM
+/// for its entire lifetime. The handle is cheap to clone and can be shared
+/// freely between threads. The workers shut down once the last handle is dropped.
+#[derive(Clone)]
+pub struct RenderPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// The amount of worker threads to spawn
+    workers: usize,
+    /// Configuration used for every document rendered by the pool
+    config: Arc<DocumentConfiguration>,
+    /// Channel to the workers, initialized on first use
+    sender: OnceLock<Mutex<Sender<Job>>>,
+    /// Guards spawning of the workers
+    spawn_lock: Mutex<()>,
+}
+
+impl RenderPool {
+    /// Create a new render pool with at most `workers` concurrent renders.
+    /// Neither the JVM nor the worker threads are started until the first render.
+    ///
+    /// A `workers` value of `0` is treated as `1`.
+    pub fn new(workers: usize, config: DocumentConfiguration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                workers: workers.max(1),
+                config: Arc::new(config),
+                sender: OnceLock::new(),
+                spawn_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Render a document to PDF bytes.
+    /// Blocks the calling thread until a worker has finished rendering the document.
+    ///
+    /// # Errors
+    ///
+    /// - If the JVM cannot be started
+    /// - If a JNI error occurs while rendering
+    /// - If rendering panicked
+    /// - If the worker threads are no longer available
+    pub fn render<D>(&self, document: D) -> Result<Vec<u8>, Error>
+    where
+        D: PdfRenderable + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let config = self.config().clone();
+        self.submit(Box::new(move |env| {
+            let _ = tx.send(render_document(&document, &config, env));
+        }))?;
+
+        rx.recv().map_err(|_| Error::PoolUnavailable)?
+    }
+
+    /// Hand a job to the workers, spawning them if that has not happened yet.
+    ///
+    /// # Errors
+    ///
+    /// - If the JVM cannot be started
+    /// - If the worker threads are no longer available
+    pub(crate) fn submit(&self, job: Job) -> Result<(), Error> {
+        self.sender()?
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .send(job)
+            .map_err(|_| Error::PoolUnavailable)
+    }
+
+    /// Whether the JVM and the workers have been started
+    #[cfg(feature = "async")]
+    pub(crate) fn is_started(&self) -> bool {
+        self.inner.sender.get().is_some()
+    }
+
+    /// The configuration used for every document rendered by the pool
+    pub(crate) fn config(&self) -> &Arc<DocumentConfiguration> {
+        &self.inner.config
+    }
+
+    /// Get the channel to the workers, starting the JVM and spawning the workers on first use.
+    /// Waits until every worker is attached to the JVM.
+    ///
+    /// # Errors
+    ///
+    /// - If the JVM cannot be started
+    /// - If a worker thread cannot be spawned or attached to the JVM
+    fn sender(&self) -> Result<&Mutex<Sender<Job>>, Error> {
+        if let Some(sender) = self.inner.sender.get() {
+            return Ok(sender);
+        }
+
+        let _guard = self
+            .inner
+            .spawn_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(sender) = self.inner.sender.get() {
+            return Ok(sender);
+        }
+
+        let jvm = JVM::global()?;
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+        let (attached_tx, attached_rx) = mpsc::channel();
+
+        for idx in 0..self.inner.workers {
+            let rx = rx.clone();
+            let attached = attached_tx.clone();
+            thread::Builder::new()
+                .name(format!("pdf-render-{idx}"))
+                .spawn(move || worker(jvm, &rx, &attached))?;
+        }
+        drop(attached_tx);
+
+        // If any worker failed to attach, the others exit once `tx` is dropped,
+        // and the next render tries again
+        for _ in 0..self.inner.workers {
+            attached_rx.recv().map_err(|_| Error::PoolUnavailable)??;
+        }
+
+        Ok(self.inner.sender.get_or_init(|| Mutex::new(tx)))
+    }
+}
+
+/// Render a document to PDF bytes in a fresh local reference frame.
+/// A Java exception thrown while rendering is cleared, so the worker can be reused.
+/// The same applies to a panic, which is returned as [Error::RenderPanicked].
+///
+/// # Errors
+///
+/// - If a JNI error occurs
+/// - If rendering panicked
+pub(crate) fn render_document<D: PdfRenderable>(
+    document: &D,
+    config: &DocumentConfiguration,
+    env: &mut JNIEnv<'_>,
+) -> Result<Vec<u8>, Error> {
+    // The panic is caught inside the frame, so unwinding does not skip popping it
+    env.with_local_frame(LOCAL_FRAME_CAPACITY, |env| {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let target = RenderTarget::new(config, env)?;
+            document
+                .render(&target, env)
+                .map_err(|e| e.resolve_java_exception(env))?;
+            target.finish(env)
+        }));
+
+        result.unwrap_or_else(|payload| {
+            // The panic may have interrupted a JNI call which threw
+            let _ = env.exception_clear();
+            Err(Error::RenderPanicked(panic_message(payload.as_ref())))
+        })
+    })
+}
+
+/// The message of a panic, if it has one
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Main loop of a worker thread.
+/// Attaches to the JVM, reports whether that succeeded through `attached`,
+/// and executes jobs until the channel is closed.
+fn worker(jvm: &'static JVM, jobs: &Mutex<Receiver<Job>>, attached: &Sender<Result<(), Error>>) {
+    let mut env = match jvm.attach() {
+        Ok(env) => env,
+        Err(e) => {
+            let _ = attached.send(Err(e));
+            return;
+        }
+    };
+    let _ = attached.send(Ok(()));
+
+    loop {
+        let job = match jobs.lock().unwrap_or_else(PoisonError::into_inner).recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+
+        // Jobs catch their own panics, see [render_document].
+        // Should one slip through, the worker must survive it: it is never respawned.
+        // The job runs in its own frame, which is popped after a panic as well,
+        // so local references cannot pile up on this long-lived thread.
+        let _ = env.with_local_frame(LOCAL_FRAME_CAPACITY, |env| {
+            if panic::catch_unwind(AssertUnwindSafe(|| job(env))).is_err() {
+                let _ = env.exception_clear();
+            }
+            Ok::<_, jni::errors::Error>(())
+        });
+    }
+}
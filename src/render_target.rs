@@ -1,9 +1,18 @@
-use crate::Error;
-use itext::itext::io::{FontProgramFactory, PdfEncodings};
-use itext::itext::kernel::{PdfDocument, PdfFontFactory, PdfWriter};
-use itext::itext::layout::{Document, ElementPropertyContainer};
+use crate::footer::Footer;
+use crate::kernel::{is_pdf_a_available, new_pdf_a_document, PdfDocumentExt};
+use crate::layout::{CanvasExt, FontFaces, FontProvider, RootElementExt};
+use crate::validate::field_path;
+use crate::{
+    DocumentMetadata, Error, Margins, Orientation, PageNumberPosition, PageNumbering, PaperSize,
+    PdfAConformanceLevel, Translations, Validate, ValidationIssue,
+};
+use itext::itext::kernel::{PageSize, PdfDocument, PdfWriter};
+use itext::itext::layout::{
+    BlockElement, Canvas, Document, ElementPropertyContainer, Paragraph, RootElement, TextAlignment,
+};
 use itext::java::ByteArrayOutputStream;
 use jni::JNIEnv;
+use std::cell::RefCell;
 
 /// A target for rendering
 pub struct RenderTarget<'a> {
@@ -12,16 +21,135 @@ pub struct RenderTarget<'a> {
     /// The document being worked on
     pub document: Document<'a>,
     pub font_size: f32,
+    /// The configured fonts, if any.
+    /// Needed for content drawn outside of the document's layout, e.g. the footer
+    fonts: Option<DocumentFonts<'a>>,
+    /// The faces provided by the configured font family
+    pub(crate) faces: FontFaces,
+    /// The texts used for the labels on the document
+    pub translations: Translations,
+    /// The page numbers printed when rendering is finished, if any
+    pub page_numbering: Option<PageNumbering>,
+    /// The configured metadata, which takes precedence over the metadata provided by documents
+    pub metadata: DocumentMetadata,
+    /// The PDF/A conformance level the document is written in, if any
+    pub pdf_a: Option<PdfAConformanceLevel>,
+    /// The footer drawn on every page when rendering is finished, see [RenderTarget::set_footer]
+    footer: RefCell<Option<Footer>>,
 }
 
+/// The fonts used for a document
+struct DocumentFonts<'a> {
+    /// Provides the fonts of all families
+    provider: FontProvider<'a>,
+    /// The font families, in order of preference
+    families: Vec<String>,
+}
+
+/// The font family used if only fallback fonts are configured
+const DEFAULT_FONT_FAMILY: &str = "helvetica";
+
+/// The distance between page numbers and the edge of the page
+const PAGE_NUMBER_OFFSET: f32 = 15.0;
+
 /// Configurations for the entire document
 pub struct DocumentConfiguration {
     /// The font family to use.
+    /// The fonts are embedded using Identity-H encoding, so all of their characters can be used.
     /// If left to None, a default is used.
-    pub font_family: Option<Vec<u8>>,
+    pub font_family: Option<FontFamily>,
+    /// Fonts used for characters missing from the font family, in order of preference.
+    /// E.g. a font with Greek or Cyrillic characters.
+    pub fallback_fonts: Vec<Vec<u8>>,
     /// The font size to use.
     /// If left to None, a default is used
     pub font_size: Option<f32>,
+    /// The texts used for the labels on the document
+    pub translations: Translations,
+    /// Print page numbers on every page.
+    /// If left to None, pages are not numbered.
+    pub page_numbering: Option<PageNumbering>,
+    /// The size of the pages
+    pub paper_size: PaperSize,
+    /// The orientation of the pages
+    pub orientation: Orientation,
+    /// The space between the content and the edges of the pages
+    pub margins: Margins,
+    /// The metadata of the document.
+    /// Fields left to None are filled in by the document being rendered, e.g. an invoice's title and seller.
+    /// The language defaults to the locale of the translations.
+    pub metadata: DocumentMetadata,
+    /// Write the document as PDF/A, e.g. for archiving invoices.
+    /// iText checks the document conforms while it is written, violations are reported as
+    /// [Error::PdfAConformance]. This requires iText's pdfa module (`com.itextpdf:pdfa`, same
+    /// version as the bundled iText) on the classpath, see [crate::JvmConfig::classpath_jar].
+    /// PDF/A requires all fonts to be embedded, so a font family must be configured.
+    /// If left to None, a regular PDF is written.
+    pub pdf_a: Option<PdfAConformanceLevel>,
+}
+
+/// The faces of a font family, as TrueType or OpenType fonts.
+/// If a face is left to None, iText simulates it using the regular face.
+#[derive(Debug, Clone)]
+pub struct FontFamily {
+    pub regular: Vec<u8>,
+    pub bold: Option<Vec<u8>>,
+    pub italic: Option<Vec<u8>>,
+    pub bold_italic: Option<Vec<u8>>,
+}
+
+impl Validate for DocumentConfiguration {
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+        let (width, height) = self.paper_size.dimensions(self.orientation);
+        if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+            issues.push(ValidationIssue {
+                path: field_path(path, "paper_size"),
+                message: format!("{width} x {height} is not a positive size"),
+            });
+            return;
+        }
+
+        let margins = &self.margins;
+        let sides = [
+            ("top", margins.top),
+            ("right", margins.right),
+            ("bottom", margins.bottom),
+            ("left", margins.left),
+        ];
+        for (side, margin) in sides {
+            if !(margin >= 0.0 && margin.is_finite()) {
+                issues.push(ValidationIssue {
+                    path: field_path(&field_path(path, "margins"), side),
+                    message: format!("{margin} is negative or not finite"),
+                });
+            }
+        }
+
+        if margins.left + margins.right >= width {
+            issues.push(ValidationIssue {
+                path: field_path(path, "margins"),
+                message: format!("left and right margins do not fit on a page {width} wide"),
+            });
+        }
+        if margins.top + margins.bottom >= height {
+            issues.push(ValidationIssue {
+                path: field_path(path, "margins"),
+                message: format!("top and bottom margins do not fit on a page {height} high"),
+            });
+        }
+    }
+}
+
+impl FontFamily {
+    /// A family consisting of only a regular face
+    pub fn new(regular: Vec<u8>) -> Self {
+        Self {
+            regular,
+            bold: None,
+            italic: None,
+            bold_italic: None,
+        }
+    }
 }
 
 impl<'a> RenderTarget<'a> {
@@ -29,49 +157,297 @@ impl<'a> RenderTarget<'a> {
     ///
     /// # Errors
     ///
-    /// If a JNI error occurs
+    /// If the configuration is invalid, see [Validate], or if a JNI error occurs
     pub fn new(config: &DocumentConfiguration, env: &mut JNIEnv<'a>) -> Result<Self, Error> {
+        Self::create(config, env).map_err(|e| e.resolve_java_exception(env))
+    }
+
+    /// Create the document and apply the configuration to it.
+    ///
+    /// # Errors
+    ///
+    /// If the configuration is invalid, or if a JNI error occurs
+    fn create(config: &DocumentConfiguration, env: &mut JNIEnv<'a>) -> Result<Self, Error> {
+        config.check()?;
+
+        if let Some(level) = config.pdf_a {
+            // iText's standard fonts are never embedded. iText would only notice when the
+            // document is closed, after all the work of rendering it.
+            if config.font_family.is_none() {
+                return Err(Error::PdfAConformance {
+                    reason: format!(
+                        "{level} requires all fonts to be embedded, but no font family is configured"
+                    ),
+                });
+            }
+
+            if !is_pdf_a_available(env) {
+                return Err(Error::PdfAUnavailable);
+            }
+        }
+
         let byte_stream = ByteArrayOutputStream::new(env)?;
-        let document = Document::new(
-            &PdfDocument::new(&PdfWriter::new(&byte_stream, env)?, env)?,
+        let writer = PdfWriter::new(&byte_stream, env)?;
+        let pdf = match config.pdf_a {
+            Some(level) => new_pdf_a_document(&writer, level, env)?,
+            None => PdfDocument::new(&writer, env)?,
+        };
+
+        let (width, height) = config.paper_size.dimensions(config.orientation);
+        // Pages are kept until the document is closed, so the footer can be drawn on every page
+        let document =
+            Document::new_with_flush(&pdf, &PageSize::new(width, height, env)?, false, env)?;
+
+        let margins = &config.margins;
+        document.set_margins(
+            margins.top,
+            margins.right,
+            margins.bottom,
+            margins.left,
             env,
         )?;
 
-        document.set_margins(40.0, 30.0, 40.0, 30.0, env)?;
-
-        if let Some(font_family) = &config.font_family {
-            Self::configure_font_family(&document, font_family, env)?;
-        }
+        let fonts = Self::configure_fonts(&document, config, env)?;
 
         if let Some(font_size) = config.font_size {
             document.set_font_size(font_size, env)?;
         }
 
-        Ok(Self {
+        pdf.add_xmp_metadata(config.pdf_a, env)?;
+
+        let target = Self {
             byte_stream,
             document,
             font_size: config.font_size.unwrap_or(11.0),
-        })
+            fonts,
+            faces: FontFaces {
+                bold: config
+                    .font_family
+                    .as_ref()
+                    .is_some_and(|family| family.bold.is_some()),
+                italic: config
+                    .font_family
+                    .as_ref()
+                    .is_some_and(|family| family.italic.is_some()),
+            },
+            translations: config.translations.clone(),
+            page_numbering: config.page_numbering.clone(),
+            metadata: config.metadata.clone(),
+            pdf_a: config.pdf_a,
+            footer: RefCell::new(None),
+        };
+        target.apply_metadata(&DocumentMetadata::default(), env)?;
+
+        Ok(target)
     }
 
-    /// Configure the font family for the current document
+    /// Configure the font family and fallback fonts for the current document.
+    /// Returns None if no fonts are configured, in which case iText's default font is used.
     ///
     /// # Errors
     ///
     /// If a JNI error occurs
-    fn configure_font_family(
+    fn configure_fonts(
         document: &Document<'a>,
-        family: &[u8],
+        config: &DocumentConfiguration,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<Option<DocumentFonts<'a>>, Error> {
+        if config.font_family.is_none() && config.fallback_fonts.is_empty() {
+            return Ok(None);
+        }
+
+        let provider = FontProvider::new(env)?;
+        let mut families = Vec::new();
+
+        match &config.font_family {
+            Some(font_family) => {
+                let faces = [
+                    Some(&font_family.regular),
+                    font_family.bold.as_ref(),
+                    font_family.italic.as_ref(),
+                    font_family.bold_italic.as_ref(),
+                ];
+
+                for face in faces.into_iter().flatten() {
+                    let family = provider.add_font(face, env)?;
+                    if !families.contains(&family) {
+                        families.push(family);
+                    }
+                }
+            }
+            None => {
+                provider.add_standard_pdf_fonts(env)?;
+                families.push(DEFAULT_FONT_FAMILY.to_string());
+            }
+        }
+
+        for fallback in &config.fallback_fonts {
+            let family = provider.add_font(fallback, env)?;
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+
+        let fonts = DocumentFonts { provider, families };
+        fonts.apply(document, env)?;
+
+        Ok(Some(fonts))
+    }
+
+    /// Use the document's fonts for content drawn outside of the document's layout, e.g. on a [Canvas].
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub(crate) fn apply_fonts<E: RootElement<'a>>(
+        &self,
+        element: &E,
         env: &mut JNIEnv<'a>,
     ) -> Result<(), Error> {
-        let font_program = FontProgramFactory::new_from_ttf(family, env)?;
-        let pdf_font = PdfFontFactory::create_from_program_with_encoding_embedded(
-            font_program,
-            PdfEncodings::Winansi,
-            env,
-        )?;
+        if let Some(fonts) = &self.fonts {
+            fonts.apply(element, env)?;
+        }
 
-        document.set_font(&pdf_font, env)?;
+        Ok(())
+    }
+
+    /// Store the metadata in the document.
+    /// The configured metadata takes precedence over `defaults`,
+    /// which is normally provided by the document being rendered.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub fn apply_metadata(
+        &self,
+        defaults: &DocumentMetadata,
+        env: &mut JNIEnv<'a>,
+    ) -> Result<(), Error> {
+        let metadata = self.metadata.clone().or(defaults.clone());
+        let pdf = self.document.get_pdf_document(env)?;
+
+        let info = [
+            ("setTitle", metadata.title),
+            ("setAuthor", metadata.author),
+            ("setSubject", metadata.subject),
+            (
+                "setKeywords",
+                Some(metadata.keywords.join(", ")).filter(|keywords| !keywords.is_empty()),
+            ),
+            ("setCreator", metadata.creator),
+        ];
+        for (setter, value) in info {
+            if let Some(value) = value {
+                pdf.set_document_info(setter, &value, env)?;
+            }
+        }
+
+        let language = metadata
+            .language
+            .as_deref()
+            .unwrap_or_else(|| self.translations.locale.language_tag());
+        pdf.set_language(language, env)?;
+
+        Ok(())
+    }
+
+    /// The width of the content of a page, i.e. the width of the page between the margins.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    pub(crate) fn content_width(&self, env: &mut JNIEnv<'a>) -> Result<f32, Error> {
+        let page_width = self
+            .document
+            .get_pdf_document(env)?
+            .get_default_page_size(env)?
+            .get_width(env)?;
+
+        Ok(page_width
+            - self.document.get_left_margin(env)?
+            - self.document.get_right_margin(env)?)
+    }
+
+    /// Draw the footer at the bottom of every page when rendering is finished.
+    /// Room for it is reserved right away, so it must be set before any content is added.
+    ///
+    /// # Errors
+    ///
+    /// - If content has already been added to the document, see [Error::FooterAfterContent]
+    /// - If the document already has a footer, see [Error::FooterAlreadySet]
+    /// - If a JNI error occurs
+    pub fn set_footer(&self, footer: &Footer, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        if self.footer.borrow().is_some() {
+            return Err(Error::FooterAlreadySet);
+        }
+        // iText creates the first page when the first content is laid out
+        if self
+            .document
+            .get_pdf_document(env)?
+            .get_number_of_pages(env)?
+            > 0
+        {
+            return Err(Error::FooterAfterContent);
+        }
+
+        footer.reserve_space(self, env)?;
+        self.footer.replace(Some(footer.clone()));
+
+        Ok(())
+    }
+
+    /// Print page numbers on every page of the document.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn number_pages(&self, numbering: &PageNumbering, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        let pdf = self.document.get_pdf_document(env)?;
+        let pages = pdf.get_number_of_pages(env)?;
+
+        let left = self.document.get_left_margin(env)?;
+        let right = self.document.get_right_margin(env)?;
+
+        let alignment = match numbering.position {
+            PageNumberPosition::TopLeft | PageNumberPosition::BottomLeft => TextAlignment::Left,
+            PageNumberPosition::TopCenter | PageNumberPosition::BottomCenter => {
+                TextAlignment::Center
+            }
+            PageNumberPosition::TopRight | PageNumberPosition::BottomRight => TextAlignment::Right,
+        };
+
+        for page_num in 1..=pages {
+            let page = pdf.get_page(page_num, env)?;
+            let page_size = page.get_page_size(env)?;
+            let width = page_size.get_right(env)? - page_size.get_left(env)? - left - right;
+
+            let bottom = match numbering.position {
+                PageNumberPosition::TopLeft
+                | PageNumberPosition::TopCenter
+                | PageNumberPosition::TopRight => {
+                    page_size.get_top(env)? - PAGE_NUMBER_OFFSET - numbering.font_size
+                }
+                PageNumberPosition::BottomLeft
+                | PageNumberPosition::BottomCenter
+                | PageNumberPosition::BottomRight => {
+                    page_size.get_bottom(env)? + PAGE_NUMBER_OFFSET
+                }
+            };
+
+            let canvas = Canvas::new_from_page(&page, &page_size, env)?;
+            self.apply_fonts(&canvas, env)?;
+
+            let text = numbering.text(page_num, pages, &self.translations);
+            let paragraph = Paragraph::new_with_text(&text, env)?;
+            paragraph
+                .set_font_size(numbering.font_size, env)?
+                .set_text_alignment(alignment.clone(), env)?
+                .set_margin_top(0.0, env)?
+                .set_margin_bottom(0.0, env)?
+                .set_fixed_position(left, bottom, width, env)?;
+            canvas.add(paragraph, env)?;
+            canvas.close(env)?;
+        }
 
         Ok(())
     }
@@ -82,8 +458,96 @@ impl<'a> RenderTarget<'a> {
     ///
     /// If a JNI error occurs
     pub fn finish(self, env: &mut JNIEnv<'a>) -> Result<Vec<u8>, Error> {
-        self.document.close(env)?;
-        let out = self.byte_stream.to_byte_array(env)?;
+        if let Some(footer) = self.footer.take() {
+            footer
+                .draw(&self, env)
+                .map_err(|e| e.resolve_java_exception(env))?;
+        }
+
+        if let Some(numbering) = &self.page_numbering {
+            self.number_pages(numbering, env)
+                .map_err(|e| e.resolve_java_exception(env))?;
+        }
+
+        let out = self
+            .document
+            .close(env)
+            .and_then(|_| self.byte_stream.to_byte_array(env))
+            .map_err(|e| Error::from(e).resolve_java_exception(env))?;
         Ok(out)
     }
 }
+
+impl<'a> DocumentFonts<'a> {
+    /// Use the fonts for all content of the element.
+    ///
+    /// # Errors
+    ///
+    /// If a JNI error occurs
+    fn apply<E: RootElement<'a>>(&self, element: &E, env: &mut JNIEnv<'a>) -> Result<(), Error> {
+        element
+            .set_font_provider(&self.provider, env)?
+            .set_font_family(&self.families, env)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Locale;
+
+    fn config(paper_size: PaperSize, margins: Margins) -> DocumentConfiguration {
+        DocumentConfiguration {
+            font_family: None,
+            fallback_fonts: Vec::new(),
+            font_size: None,
+            translations: Translations::new(Locale::Dutch),
+            page_numbering: None,
+            paper_size,
+            orientation: Orientation::Portrait,
+            margins,
+            metadata: DocumentMetadata::default(),
+            pdf_a: None,
+        }
+    }
+
+    #[test]
+    fn default_configuration_is_valid() {
+        assert_eq!(
+            config(PaperSize::A4, Margins::default()).validate(),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn reports_invalid_margins() {
+        let margins = Margins {
+            top: -1.0,
+            right: 300.0,
+            bottom: f32::NAN,
+            left: 300.0,
+        };
+        let issues = config(PaperSize::A4, margins).validate();
+        let messages: Vec<_> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "margins.top: -1 is negative or not finite",
+                "margins.bottom: NaN is negative or not finite",
+                "margins: left and right margins do not fit on a page 595 wide",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_paper_size() {
+        let paper_size = PaperSize::Custom {
+            width: 0.0,
+            height: 842.0,
+        };
+        let issues = config(paper_size, Margins::default()).validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "paper_size");
+    }
+}
@@ -0,0 +1,438 @@
+//! Invoices as UBL 2.1 XML, following Peppol BIS Billing 3.0.
+
+use crate::header::AddressableParty;
+use crate::invoice::{
+    Invoice, InvoiceItem, VatRate, COMMERCIAL_INVOICE, SEPA_CREDIT_TRANSFER, UNIT_CODE,
+};
+use crate::validate::field_path;
+use crate::xml::{base64, XmlWriter};
+use crate::{Error, ValidationIssue};
+
+/// The specification the invoice conforms to
+const PEPPOL_CUSTOMIZATION: &str =
+    "urn:cen.eu:en16931:2017#compliant#urn:fdc:peppol.eu:2017:poacc:billing:3.0";
+
+/// The business process the invoice is part of
+const PEPPOL_PROFILE: &str = "urn:fdc:peppol.eu:2017:poacc:billing:01:1.0";
+
+/// The registers Dutch sellers must be identified in (NL-R-003): the Chamber of Commerce (KvK)
+/// and the government organisation identifier (OIN)
+const DUTCH_LEGAL_REGISTRATION_SCHEMES: [&str; 2] = ["0106", "0190"];
+
+/// VATEX code of reverse-charged VAT
+const REVERSE_CHARGE_EXEMPTION_CODE: &str = "VATEX-EU-AE";
+
+impl Invoice {
+    /// Generate the UBL 2.1 XML of this invoice, following Peppol BIS Billing 3.0.
+    /// If provided, the rendered PDF is embedded as attachment.
+    /// Amounts are rounded using the invoice's [crate::Rounding].
+    ///
+    /// # Errors
+    ///
+    /// If the invoice lacks information required for Peppol invoices, e.g. the electronic addresses
+    /// of the parties or the KvK number and bank account of a Dutch seller,
+    /// or if its totals do not equal the sums of its lines and VAT breakdown
+    pub fn to_ubl(&self, pdf: Option<&[u8]>) -> Result<String, Error> {
+        let mut issues = Vec::new();
+        self.validate_e_invoice_at("", &mut issues);
+        let parties = [
+            ("header.seller", &self.header.seller),
+            ("header.addressed_to", &self.header.addressed_to),
+        ];
+        for (path, party) in parties {
+            if party.electronic_address.is_none() {
+                issues.push(ValidationIssue {
+                    path: field_path(path, "electronic_address"),
+                    message: "is required for Peppol invoices".to_string(),
+                });
+            }
+        }
+        let seller = &self.header.seller;
+        if seller.address.country_code.as_deref() == Some("NL") {
+            let registered = seller
+                .legal_registration
+                .as_ref()
+                .is_some_and(|registration| {
+                    DUTCH_LEGAL_REGISTRATION_SCHEMES.contains(&registration.scheme.as_str())
+                });
+            if !registered {
+                issues.push(ValidationIssue {
+                    path: "header.seller.legal_registration".to_string(),
+                    message: "must be a KvK number (0106) or OIN (0190) for Dutch sellers"
+                        .to_string(),
+                });
+            }
+            // NL-R-007 requires payment means, which are written for the bank account
+            if seller.bank_account.is_none() {
+                issues.push(ValidationIssue {
+                    path: "header.seller.bank_account".to_string(),
+                    message: "is required for Peppol invoices of Dutch sellers".to_string(),
+                });
+            }
+        }
+        if !issues.is_empty() {
+            return Err(Error::Validation(issues));
+        }
+
+        let currency = self.currency.code();
+        let mut xml = XmlWriter::new();
+        xml.start(
+            "Invoice",
+            &[
+                (
+                    "xmlns",
+                    "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2",
+                ),
+                (
+                    "xmlns:cac",
+                    "urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2",
+                ),
+                (
+                    "xmlns:cbc",
+                    "urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2",
+                ),
+            ],
+        );
+
+        xml.text("cbc:CustomizationID", &[], PEPPOL_CUSTOMIZATION)
+            .text("cbc:ProfileID", &[], PEPPOL_PROFILE)
+            .text("cbc:ID", &[], &self.invoice_id)
+            .text(
+                "cbc:IssueDate",
+                &[],
+                &self.invoice_date.format("%Y-%m-%d").to_string(),
+            );
+        if let Some(due_date) = self.expiration_date() {
+            xml.text("cbc:DueDate", &[], &due_date.format("%Y-%m-%d").to_string());
+        }
+        xml.text("cbc:InvoiceTypeCode", &[], COMMERCIAL_INVOICE);
+        // PEPPOL-EN16931-R008 forbids empty elements
+        if let Some(note) = self.note.as_deref().filter(|note| !note.trim().is_empty()) {
+            xml.text("cbc:Note", &[], note);
+        }
+        xml.text("cbc:DocumentCurrencyCode", &[], currency);
+        if !self.reference.trim().is_empty() {
+            xml.text("cbc:BuyerReference", &[], &self.reference);
+        }
+        xml.start("cac:OrderReference", &[])
+            .text("cbc:ID", &[], &self.order_id)
+            .end();
+
+        if let Some(pdf) = pdf {
+            let file_name = format!("{}.pdf", self.invoice_id);
+            xml.start("cac:AdditionalDocumentReference", &[])
+                .text("cbc:ID", &[], &self.invoice_id)
+                .start("cac:Attachment", &[])
+                .text(
+                    "cbc:EmbeddedDocumentBinaryObject",
+                    &[("mimeCode", "application/pdf"), ("filename", &file_name)],
+                    &base64(pdf),
+                )
+                .end()
+                .end();
+        }
+
+        xml.start("cac:AccountingSupplierParty", &[]);
+        write_party(&mut xml, &self.header.seller);
+        xml.end();
+        xml.start("cac:AccountingCustomerParty", &[]);
+        write_party(&mut xml, &self.header.addressed_to);
+        xml.end();
+
+        if let Some(bank_account) = &self.header.seller.bank_account {
+            xml.start("cac:PaymentMeans", &[])
+                .text("cbc:PaymentMeansCode", &[], SEPA_CREDIT_TRANSFER)
+                .text("cbc:PaymentID", &[], &self.invoice_id)
+                .start("cac:PayeeFinancialAccount", &[])
+                .text("cbc:ID", &[], &bank_account.normalized_iban());
+            if let Some(bic) = &bank_account.bic {
+                xml.start("cac:FinancialInstitutionBranch", &[])
+                    .text("cbc:ID", &[], bic)
+                    .end();
+            }
+            xml.end().end();
+        }
+
+        xml.start("cac:TaxTotal", &[]).text(
+            "cbc:TaxAmount",
+            &[("currencyID", currency)],
+            &self.e_invoice_amount(self.totals.total_vat),
+        );
+        for summary in self.vat_breakdown() {
+            xml.start("cac:TaxSubtotal", &[])
+                .text(
+                    "cbc:TaxableAmount",
+                    &[("currencyID", currency)],
+                    &self.e_invoice_amount(summary.base_amount),
+                )
+                .text(
+                    "cbc:TaxAmount",
+                    &[("currencyID", currency)],
+                    &self.e_invoice_amount(summary.vat_amount),
+                );
+            write_tax_category(&mut xml, "cac:TaxCategory", &summary.rate, true);
+            xml.end();
+        }
+        xml.end();
+
+        // The totals equal the sums of the lines and the VAT breakdown, see [Invoice::validate_e_invoice_at]
+        let totals = &self.totals;
+        let amounts = [
+            ("cbc:LineExtensionAmount", self.e_invoice_line_total()),
+            ("cbc:TaxExclusiveAmount", totals.total_excluding_vat),
+            ("cbc:TaxInclusiveAmount", totals.total_including_vat),
+            ("cbc:PayableAmount", totals.total_including_vat),
+        ];
+        xml.start("cac:LegalMonetaryTotal", &[]);
+        for (name, amount) in amounts {
+            xml.text(
+                name,
+                &[("currencyID", currency)],
+                &self.e_invoice_amount(amount),
+            );
+        }
+        xml.end();
+
+        for (idx, item) in self.items.iter().enumerate() {
+            self.write_ubl_line(&mut xml, idx + 1, item);
+        }
+
+        xml.end();
+        Ok(xml.finish())
+    }
+
+    /// Write an invoice line
+    fn write_ubl_line(&self, xml: &mut XmlWriter, line: usize, item: &InvoiceItem) {
+        let currency = self.currency.code();
+
+        xml.start("cac:InvoiceLine", &[])
+            .text("cbc:ID", &[], &line.to_string())
+            .text(
+                "cbc:InvoicedQuantity",
+                &[("unitCode", UNIT_CODE)],
+                &item.quantity.to_string(),
+            )
+            .text(
+                "cbc:LineExtensionAmount",
+                &[("currencyID", currency)],
+                &self.e_invoice_amount(item.total_price),
+            );
+
+        xml.start("cac:Item", &[])
+            .text("cbc:Name", &[], &item.description);
+        if !item.identifier.trim().is_empty() {
+            xml.start("cac:SellersItemIdentification", &[])
+                .text("cbc:ID", &[], &item.identifier)
+                .end();
+        }
+        write_tax_category(xml, "cac:ClassifiedTaxCategory", &item.vat_rate, false);
+        xml.end();
+
+        xml.start("cac:Price", &[]).text(
+            "cbc:PriceAmount",
+            &[("currencyID", currency)],
+            &item.subtotal_price_per_unit.normalize().to_string(),
+        );
+        if !item.discount_percentage.is_zero() {
+            let discount = item.price_per_unit - item.subtotal_price_per_unit;
+            xml.start("cac:AllowanceCharge", &[])
+                .text("cbc:ChargeIndicator", &[], "false")
+                .text(
+                    "cbc:Amount",
+                    &[("currencyID", currency)],
+                    &discount.normalize().to_string(),
+                )
+                .text(
+                    "cbc:BaseAmount",
+                    &[("currencyID", currency)],
+                    &item.price_per_unit.normalize().to_string(),
+                )
+                .end();
+        }
+        xml.end();
+
+        xml.end();
+    }
+}
+
+/// Write a trading party, which must have a country code and electronic address
+fn write_party(xml: &mut XmlWriter, party: &AddressableParty) {
+    let address = &party.address;
+
+    xml.start("cac:Party", &[]);
+    if let Some(electronic_address) = &party.electronic_address {
+        xml.text(
+            "cbc:EndpointID",
+            &[("schemeID", &electronic_address.scheme)],
+            &electronic_address.identifier,
+        );
+    }
+
+    xml.start("cac:PostalAddress", &[])
+        .text(
+            "cbc:StreetName",
+            &[],
+            &format!("{} {}", address.street, address.number),
+        )
+        .text("cbc:CityName", &[], &address.city);
+    if !address.zipcode.trim().is_empty() {
+        xml.text("cbc:PostalZone", &[], &address.zipcode);
+    }
+    xml.start("cac:Country", &[])
+        .text(
+            "cbc:IdentificationCode",
+            &[],
+            address.country_code.as_deref().unwrap_or_default(),
+        )
+        .end()
+        .end();
+
+    if let Some(vat_number) = &party.vat_number {
+        xml.start("cac:PartyTaxScheme", &[])
+            .text("cbc:CompanyID", &[], vat_number)
+            .start("cac:TaxScheme", &[])
+            .text("cbc:ID", &[], "VAT")
+            .end()
+            .end();
+    }
+
+    xml.start("cac:PartyLegalEntity", &[])
+        .text("cbc:RegistrationName", &[], &party.name);
+    if let Some(legal_registration) = &party.legal_registration {
+        xml.text(
+            "cbc:CompanyID",
+            &[("schemeID", &legal_registration.scheme)],
+            &legal_registration.identifier,
+        );
+    }
+    xml.end();
+    xml.end();
+}
+
+/// Write the VAT category of a rate.
+/// Exemption reasons are only allowed in the VAT breakdown, not on invoice lines.
+fn write_tax_category(xml: &mut XmlWriter, name: &str, rate: &VatRate, with_exemption: bool) {
+    xml.start(name, &[])
+        .text("cbc:ID", &[], rate.category_code())
+        .text(
+            "cbc:Percent",
+            &[],
+            &rate.percentage().normalize().to_string(),
+        );
+    if with_exemption {
+        if *rate == VatRate::ReverseCharge {
+            xml.text(
+                "cbc:TaxExemptionReasonCode",
+                &[],
+                REVERSE_CHARGE_EXEMPTION_CODE,
+            );
+        }
+        if let Some(reason) = rate.exemption_reason() {
+            xml.text("cbc:TaxExemptionReason", &[], reason);
+        }
+    }
+    xml.start("cac:TaxScheme", &[])
+        .text("cbc:ID", &[], "VAT")
+        .end()
+        .end();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header::ElectronicAddress;
+    use crate::invoice::tests::sample_invoice;
+    use crate::invoice::Invoice;
+    use crate::Error;
+
+    /// The sample invoice, addressed to a customer which receives Peppol invoices
+    fn peppol_invoice() -> Invoice {
+        let mut invoice = sample_invoice();
+        invoice.header.addressed_to.electronic_address = Some(ElectronicAddress {
+            scheme: "0106".to_string(),
+            identifier: "12345678".to_string(),
+        });
+        invoice
+    }
+
+    fn issue_paths(invoice: &Invoice) -> Vec<String> {
+        let Err(Error::Validation(issues)) = invoice.to_ubl(None) else {
+            panic!("expected a validation error");
+        };
+        issues.into_iter().map(|issue| issue.path).collect()
+    }
+
+    #[test]
+    fn writes_sample_invoice() {
+        let xml = peppol_invoice().to_ubl(Some(b"foo")).unwrap();
+
+        for expected in [
+            "<cbc:ID>230307</cbc:ID>\n  <cbc:IssueDate>2023-06-19</cbc:IssueDate>\n  \
+             <cbc:DueDate>2023-07-19</cbc:DueDate>\n  <cbc:InvoiceTypeCode>380</cbc:InvoiceTypeCode>",
+            "<cbc:EmbeddedDocumentBinaryObject mimeCode=\"application/pdf\" filename=\"230307.pdf\">\
+             Zm9v</cbc:EmbeddedDocumentBinaryObject>",
+            "<cbc:EndpointID schemeID=\"0106\">67223370</cbc:EndpointID>",
+            "<cac:PartyLegalEntity>\n        <cbc:RegistrationName>Mr.Friendly B.V.</cbc:RegistrationName>\n        \
+             <cbc:CompanyID schemeID=\"0106\">67223370</cbc:CompanyID>\n      </cac:PartyLegalEntity>",
+            "<cbc:RegistrationName>Smith &amp; Sons</cbc:RegistrationName>\n      </cac:PartyLegalEntity>",
+            "</cac:AccountingCustomerParty>\n  <cac:PaymentMeans>\n    \
+             <cbc:PaymentMeansCode>58</cbc:PaymentMeansCode>\n    <cbc:PaymentID>230307</cbc:PaymentID>\n    \
+             <cac:PayeeFinancialAccount>\n      <cbc:ID>NL75INGB0007538383</cbc:ID>\n      \
+             <cac:FinancialInstitutionBranch>\n        <cbc:ID>INGBNL2A</cbc:ID>\n      \
+             </cac:FinancialInstitutionBranch>\n    </cac:PayeeFinancialAccount>\n  </cac:PaymentMeans>\n  \
+             <cac:TaxTotal>",
+            "<cac:TaxTotal>\n    <cbc:TaxAmount currencyID=\"EUR\">26.08</cbc:TaxAmount>",
+            "<cbc:TaxableAmount currencyID=\"EUR\">9.95</cbc:TaxableAmount>\n      \
+             <cbc:TaxAmount currencyID=\"EUR\">0.90</cbc:TaxAmount>",
+            "<cac:LegalMonetaryTotal>\n    \
+             <cbc:LineExtensionAmount currencyID=\"EUR\">129.85</cbc:LineExtensionAmount>\n    \
+             <cbc:TaxExclusiveAmount currencyID=\"EUR\">129.85</cbc:TaxExclusiveAmount>\n    \
+             <cbc:TaxInclusiveAmount currencyID=\"EUR\">155.93</cbc:TaxInclusiveAmount>\n    \
+             <cbc:PayableAmount currencyID=\"EUR\">155.93</cbc:PayableAmount>\n  \
+             </cac:LegalMonetaryTotal>",
+            "<cbc:InvoicedQuantity unitCode=\"C62\">2</cbc:InvoicedQuantity>",
+        ] {
+            assert!(xml.contains(expected), "missing {expected:?} in\n{xml}");
+        }
+        assert!(xml.ends_with("</Invoice>\n"));
+    }
+
+    #[test]
+    fn requires_electronic_addresses() {
+        assert_eq!(
+            issue_paths(&sample_invoice()),
+            ["header.addressed_to.electronic_address"]
+        );
+    }
+
+    #[test]
+    fn requires_legal_registration_of_dutch_sellers() {
+        let mut invoice = peppol_invoice();
+        invoice.header.seller.legal_registration = None;
+        assert_eq!(issue_paths(&invoice), ["header.seller.legal_registration"]);
+
+        invoice.header.seller.address.country_code = Some("BE".to_string());
+        assert!(invoice.to_ubl(None).is_ok());
+    }
+
+    #[test]
+    fn requires_bank_account_of_dutch_sellers() {
+        let mut invoice = peppol_invoice();
+        invoice.header.seller.bank_account = None;
+        assert_eq!(issue_paths(&invoice), ["header.seller.bank_account"]);
+
+        invoice.header.seller.address.country_code = Some("BE".to_string());
+        let xml = invoice.to_ubl(None).unwrap();
+        assert!(!xml.contains("cac:PaymentMeans"));
+    }
+
+    #[test]
+    fn omits_empty_note() {
+        let mut invoice = peppol_invoice();
+        invoice.note = Some(" ".to_string());
+        assert!(!invoice.to_ubl(None).unwrap().contains("cbc:Note"));
+
+        invoice.note = Some("Thank you".to_string());
+        let xml = invoice.to_ubl(None).unwrap();
+        assert!(xml.contains("<cbc:Note>Thank you</cbc:Note>"));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::Error;
+use std::fmt;
+
+/// A problem with a single field of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Path to the offending field, e.g. `header.addressed_to.address.city` or `items[2].description`
+    pub path: String,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Something which can be checked for problems before it is rendered.
+/// Validation does not require a JVM.
+pub trait Validate {
+    /// Add all problems found in self to `issues`.
+    /// `path` is the path of self within the document, empty for the document itself.
+    fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>);
+
+    /// Get all problems found in self.
+    /// Returns an empty list if no problems were found.
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        self.validate_at("", &mut issues);
+        issues
+    }
+
+    /// Validate self, turning any problems found into an error.
+    ///
+    /// # Errors
+    ///
+    /// If any problem was found
+    fn check(&self) -> Result<(), Error> {
+        let issues = self.validate();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(issues))
+        }
+    }
+}
+
+/// Join the path of a parent with the name of a field
+pub(crate) fn field_path(parent: &str, field: &str) -> String {
+    if parent.is_empty() {
+        field.to_string()
+    } else {
+        format!("{parent}.{field}")
+    }
+}
+
+/// Join the path of a list with the index of an element
+pub(crate) fn index_path(parent: &str, index: usize) -> String {
+    format!("{parent}[{index}]")
+}
+
+/// Add an issue if a required string field is empty or only whitespace
+pub(crate) fn require_non_empty(
+    value: &str,
+    parent: &str,
+    field: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if value.trim().is_empty() {
+        issues.push(ValidationIssue {
+            path: field_path(parent, field),
+            message: "must not be empty".to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_paths() {
+        assert_eq!(field_path("", "header"), "header");
+        assert_eq!(field_path("header", "seller"), "header.seller");
+        assert_eq!(index_path("items", 2), "items[2]");
+        assert_eq!(
+            field_path(&index_path(&field_path("", "items"), 0), "barcode"),
+            "items[0].barcode"
+        );
+    }
+
+    #[test]
+    fn requires_non_blank_strings() {
+        let mut issues = Vec::new();
+        require_non_empty("10315", "", "order_id", &mut issues);
+        require_non_empty(" \t", "header.seller", "name", &mut issues);
+        require_non_empty("", "", "invoice_id", &mut issues);
+
+        let paths: Vec<_> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, ["header.seller.name", "invoice_id"]);
+        assert_eq!(
+            issues[0].to_string(),
+            "header.seller.name: must not be empty"
+        );
+    }
+
+    struct Name(&'static str);
+
+    impl Validate for Name {
+        fn validate_at(&self, path: &str, issues: &mut Vec<ValidationIssue>) {
+            require_non_empty(self.0, path, "name", issues);
+        }
+    }
+
+    #[test]
+    fn check_turns_issues_into_error() {
+        assert!(Name("Mr.Friendly").check().is_ok());
+
+        let Err(Error::Validation(issues)) = Name("").check() else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(issues, Name("").validate());
+        assert_eq!(issues[0].path, "name");
+    }
+}
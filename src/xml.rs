@@ -0,0 +1,164 @@
+//! A minimal writer for the XML documents generated from invoices.
+
+/// Writes an indented XML document, element by element
+pub(crate) struct XmlWriter {
+    xml: String,
+    /// The names of the elements which have been started but not yet ended
+    open: Vec<String>,
+}
+
+impl XmlWriter {
+    pub fn new() -> Self {
+        Self {
+            xml: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+            open: Vec::new(),
+        }
+    }
+
+    /// Start an element containing other elements
+    pub fn start(&mut self, name: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        self.indent();
+        self.open_tag(name, attributes);
+        self.xml.push_str(">\n");
+        self.open.push(name.to_string());
+        self
+    }
+
+    /// End the most recently started element
+    pub fn end(&mut self) -> &mut Self {
+        let name = self.open.pop().expect("no element to end");
+        self.indent();
+        self.xml.push_str(&format!("</{name}>\n"));
+        self
+    }
+
+    /// Write an element containing only text
+    pub fn text(&mut self, name: &str, attributes: &[(&str, &str)], text: &str) -> &mut Self {
+        self.indent();
+        self.open_tag(name, attributes);
+        self.xml.push_str(&format!(">{}</{name}>\n", escape(text)));
+        self
+    }
+
+    /// The written document. All elements must have been ended.
+    pub fn finish(self) -> String {
+        debug_assert!(self.open.is_empty(), "unended elements: {:?}", self.open);
+        self.xml
+    }
+
+    fn indent(&mut self) {
+        self.xml.push_str(&"  ".repeat(self.open.len()));
+    }
+
+    fn open_tag(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.xml.push('<');
+        self.xml.push_str(name);
+        for (attribute, value) in attributes {
+            self.xml
+                .push_str(&format!(" {attribute}=\"{}\"", escape(value)));
+        }
+    }
+}
+
+/// Escape the characters which have a special meaning in XML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The alphabet of base64 encoding, as defined in RFC 4648
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode binary data using padded base64, as used for binary objects in XML
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let indices = [
+            b[0] >> 2,
+            (b[0] & 0b11) << 4 | b[1] >> 4,
+            (b[1] & 0b1111) << 2 | b[2] >> 6,
+            b[2] & 0b11_1111,
+        ];
+
+        // A chunk of n bytes is encoded in n + 1 characters, padded to 4
+        for (idx, index) in indices.into_iter().enumerate() {
+            if idx <= chunk.len() {
+                encoded.push(char::from(BASE64_ALPHABET[usize::from(index)]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_rfc_4648_vectors() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+        assert_eq!(base64(b"fooba"), "Zm9vYmE=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn base64_uses_the_whole_alphabet() {
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(base64(&[0x00, 0x10, 0x83]), "ABCD");
+    }
+
+    #[test]
+    fn escapes_text_and_attributes() {
+        let mut xml = XmlWriter::new();
+        xml.text("a", &[("b", "\"x\" & 'y'")], "<Smith & Sons>");
+        assert_eq!(
+            xml.finish(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <a b=\"&quot;x&quot; &amp; &apos;y&apos;\">&lt;Smith &amp; Sons&gt;</a>\n"
+        );
+    }
+
+    #[test]
+    fn indents_nested_elements() {
+        let mut xml = XmlWriter::new();
+        xml.start("a", &[("xmlns", "urn:a")])
+            .start("b", &[])
+            .text("c", &[], "1")
+            .end()
+            .text("d", &[], "2")
+            .end();
+        assert_eq!(
+            xml.finish(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <a xmlns=\"urn:a\">\n  <b>\n    <c>1</c>\n  </b>\n  <d>2</d>\n</a>\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no element to end")]
+    fn ending_without_start_panics() {
+        XmlWriter::new().end();
+    }
+}
@@ -0,0 +1,101 @@
+//! Validates rendered Factur-X invoices with veraPDF, which checks PDF/A-3b conformance
+//! including the embedded XML and its XMP extension schema.
+//!
+//! Requires iText's pdfa module and the veraPDF command line tool, so the test is ignored by default:
+//!
+//! ```text
+//! ITEXT_PDFA_JAR=/path/to/pdfa-7.1.18.jar VERAPDF=/path/to/verapdf cargo test -- --ignored
+//! ```
+
+use order_pdf_printer::invoice::{
+    Currency, Invoice, InvoiceItem, InvoiceTotals, PaymentTerm, TotalsPlacement, VatRate,
+};
+use order_pdf_printer::{
+    DocumentConfiguration, DocumentMetadata, FontFamily, JvmConfig, Locale, Margins, NaiveDate,
+    Orientation, PaperSize, PdfAConformanceLevel, PdfRenderable, RenderTarget, Rounding,
+    Translations, JVM,
+};
+use rust_decimal_macros::dec;
+use std::env;
+use std::process::Command;
+
+#[path = "../examples/info.rs"]
+mod info;
+
+#[test]
+#[ignore = "requires ITEXT_PDFA_JAR and VERAPDF"]
+fn factur_x_invoice_conforms_to_pdf_a_3b() {
+    let pdfa_jar = env::var("ITEXT_PDFA_JAR").expect("ITEXT_PDFA_JAR is not set");
+    let verapdf = env::var("VERAPDF").expect("VERAPDF is not set");
+
+    let jvm = JVM::with_config(&JvmConfig::new().classpath_jar(pdfa_jar)).unwrap();
+    let mut env = jvm.attach().unwrap();
+
+    let target = RenderTarget::new(
+        &DocumentConfiguration {
+            font_family: Some(FontFamily::new(
+                include_bytes!("../examples/OpenSans-Regular.ttf").to_vec(),
+            )),
+            fallback_fonts: Vec::new(),
+            font_size: None,
+            translations: Translations::new(Locale::Dutch),
+            page_numbering: None,
+            paper_size: PaperSize::A4,
+            orientation: Orientation::Portrait,
+            margins: Margins::default(),
+            metadata: DocumentMetadata::default(),
+            pdf_a: Some(PdfAConformanceLevel::PdfA3B),
+        },
+        &mut env,
+    )
+    .unwrap();
+
+    let invoice = Invoice {
+        header: info::get_header("Factuur"),
+        footer: info::get_footer(),
+        order_id: "10315".into(),
+        reference: "Foobar".into(),
+        invoice_id: "230307".into(),
+        invoice_date: NaiveDate::from_ymd_opt(2023, 6, 19).unwrap(),
+        currency: Currency::Euro,
+        note: None,
+        strict_totals: None,
+        totals_placement: TotalsPlacement::Flow,
+        factur_x: true,
+        payment_qr_code: false,
+        rounding: Rounding::HalfUp,
+        totals: InvoiceTotals {
+            total_excluding_vat: dec!(179.85),
+            total_vat: dec!(37.77),
+            total_including_vat: dec!(217.62),
+        },
+        payment_term: PaymentTerm::Net(30),
+        items: vec![
+            InvoiceItem {
+                identifier: "16005-3".to_string(),
+                description: "Set 3 stuks Mr.Friendly Filter Globe (nat)".to_string(),
+                quantity: 1,
+                price_per_unit: dec!(59.95),
+                discount_percentage: dec!(0),
+                subtotal_price_per_unit: dec!(59.95),
+                total_price: dec!(59.95),
+                vat_rate: VatRate::Percentage(dec!(21)),
+            };
+            3
+        ],
+    };
+
+    invoice.render(&target, &mut env).unwrap();
+    let pdf = target.finish(&mut env).unwrap();
+
+    let path = env::temp_dir().join("order-pdf-printer-factur-x.pdf");
+    std::fs::write(&path, pdf).unwrap();
+
+    let output = Command::new(verapdf)
+        .args(["--flavour", "3b", "--format", "text"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    let report = String::from_utf8_lossy(&output.stdout);
+    assert!(report.starts_with("PASS"), "veraPDF report: {report}");
+}